@@ -0,0 +1,42 @@
+use std::{fs, path::PathBuf};
+
+use crate::cli::ConnectOpts;
+
+const CATALOG_FILE: &str = ".taotie_catalog.json";
+
+/// The datasets registered across sessions, saved next to the REPL history.
+pub struct Catalog {
+    path: Option<PathBuf>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            path: dirs::home_dir().map(|home| home.join(CATALOG_FILE)),
+        }
+    }
+}
+
+impl Catalog {
+    /// A catalog saved to `path` rather than the home directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// The saved datasets, none if the catalog was never written.
+    pub fn load(&self) -> anyhow::Result<Vec<ConnectOpts>> {
+        match &self.path {
+            Some(path) if path.exists() => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    pub fn save(&self, datasets: &[ConnectOpts]) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            fs::write(path, serde_json::to_string_pretty(datasets)?)?;
+        }
+        Ok(())
+    }
+}
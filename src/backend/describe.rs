@@ -0,0 +1,82 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescribeMethod {
+    Total,
+    NullTotal,
+    Mean,
+    Stddev,
+    Min,
+    Max,
+    Median,
+    Percentile(u8),
+    Distinct,
+    Mode,
+    ModeFreq,
+    Skewness,
+    Kurtosis,
+    ZeroTotal,
+}
+
+impl DescribeMethod {
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            DescribeMethod::Total,
+            DescribeMethod::NullTotal,
+            DescribeMethod::Mean,
+            DescribeMethod::Stddev,
+            DescribeMethod::Min,
+            DescribeMethod::Max,
+            DescribeMethod::Median,
+        ]
+    }
+}
+
+impl fmt::Display for DescribeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescribeMethod::Total => write!(f, "Total"),
+            DescribeMethod::NullTotal => write!(f, "Null Total"),
+            DescribeMethod::Mean => write!(f, "Mean"),
+            DescribeMethod::Stddev => write!(f, "Stddev"),
+            DescribeMethod::Min => write!(f, "Min"),
+            DescribeMethod::Max => write!(f, "Max"),
+            DescribeMethod::Median => write!(f, "Median"),
+            DescribeMethod::Percentile(p) => write!(f, "Percentile {}", p),
+            DescribeMethod::Distinct => write!(f, "Distinct"),
+            DescribeMethod::Mode => write!(f, "Mode"),
+            DescribeMethod::ModeFreq => write!(f, "Mode Freq"),
+            DescribeMethod::Skewness => write!(f, "Skewness"),
+            DescribeMethod::Kurtosis => write!(f, "Kurtosis"),
+            DescribeMethod::ZeroTotal => write!(f, "Zero Total"),
+        }
+    }
+}
+
+impl FromStr for DescribeMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let method = match s.as_str() {
+            "count" | "total" => DescribeMethod::Total,
+            "null" | "nulls" => DescribeMethod::NullTotal,
+            "mean" | "avg" => DescribeMethod::Mean,
+            "std" | "stddev" => DescribeMethod::Stddev,
+            "min" => DescribeMethod::Min,
+            "max" => DescribeMethod::Max,
+            "median" => DescribeMethod::Median,
+            "distinct" | "unique" => DescribeMethod::Distinct,
+            "mode" | "top" => DescribeMethod::Mode,
+            "freq" | "mode_freq" => DescribeMethod::ModeFreq,
+            "skew" | "skewness" => DescribeMethod::Skewness,
+            "kurt" | "kurtosis" => DescribeMethod::Kurtosis,
+            "zero" | "zeros" => DescribeMethod::ZeroTotal,
+            v => match v.strip_prefix('p').map(|p| p.parse::<u8>()) {
+                Some(Ok(p)) if p <= 100 => DescribeMethod::Percentile(p),
+                _ => return Err(format!("Unsupported statistic: {}", v)),
+            },
+        };
+        Ok(method)
+    }
+}
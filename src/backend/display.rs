@@ -0,0 +1,135 @@
+//! Text layouts neither arrow nor polars can write, built from cells already
+//! formatted as strings.
+
+use std::fmt::Write;
+
+/// A grid like arrow's pretty printing, for polars whose own table elides
+/// rows and columns past a limit.
+pub fn table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths = headers
+        .iter()
+        .map(|h| h.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let border = format!(
+        "+{}+\n",
+        widths
+            .iter()
+            .map(|w| "-".repeat(w + 2))
+            .collect::<Vec<_>>()
+            .join("+")
+    );
+    let padded = |cells: &[String]| {
+        line(
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{:<w$}", c.replace('\n', " "), w = w)),
+        )
+    };
+    let mut out = border.clone();
+    out.push_str(&padded(headers));
+    out.push_str(&border);
+    for row in rows {
+        out.push_str(&padded(row));
+    }
+    if !rows.is_empty() {
+        out.push_str(&border);
+    }
+    out
+}
+
+pub fn markdown(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut out = line(headers.iter().map(|h| escape(h)));
+    out.push_str(&line(headers.iter().map(|_| "---".to_string())));
+    for row in rows {
+        out.push_str(&line(row.iter().map(|c| escape(c))));
+    }
+    out
+}
+
+/// One field per line and a separator per record, like `\x` in psql.
+pub fn vertical(headers: &[String], rows: &[Vec<String>]) -> String {
+    let width = headers.iter().map(|h| h.chars().count()).max();
+    let width = width.unwrap_or_default();
+    let mut out = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let _ = writeln!(out, "-[ RECORD {} ]{}", i + 1, "-".repeat(width + 2));
+        for (header, cell) in headers.iter().zip(row) {
+            let _ = writeln!(out, "{:<width$} | {}", header, cell);
+        }
+    }
+    if rows.is_empty() {
+        out.push_str("(0 rows)\n");
+    }
+    out
+}
+
+fn line(cells: impl Iterator<Item = String>) -> String {
+    format!("| {} |\n", cells.collect::<Vec<_>>().join(" | "))
+}
+
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn table_should_pad_every_column() {
+        let headers = strings(&["id", "name"]);
+        let rows = vec![strings(&["1", "alice"]), strings(&["22", "b\nc"])];
+        assert_eq!(
+            table(&headers, &rows),
+            "+----+-------+\n\
+             | id | name  |\n\
+             +----+-------+\n\
+             | 1  | alice |\n\
+             | 22 | b c   |\n\
+             +----+-------+\n"
+        );
+        assert_eq!(
+            table(&headers, &[]),
+            "+----+------+\n| id | name |\n+----+------+\n"
+        );
+    }
+
+    #[test]
+    fn markdown_should_escape_cells() {
+        let headers = strings(&["id", "a|b"]);
+        let rows = vec![strings(&["1", "x|y"]), strings(&["2", "multi\nline"])];
+        assert_eq!(
+            markdown(&headers, &rows),
+            "| id | a\\|b |\n\
+             | --- | --- |\n\
+             | 1 | x\\|y |\n\
+             | 2 | multi line |\n"
+        );
+    }
+
+    #[test]
+    fn vertical_should_show_a_record_per_block() {
+        let headers = strings(&["id", "name"]);
+        let rows = vec![strings(&["1", "alice"]), strings(&["2", ""])];
+        assert_eq!(
+            vertical(&headers, &rows),
+            "-[ RECORD 1 ]------\n\
+             id   | 1\n\
+             name | alice\n\
+             -[ RECORD 2 ]------\n\
+             id   | 2\n\
+             name | \n"
+        );
+        assert_eq!(vertical(&headers, &[]), "(0 rows)\n");
+    }
+}
@@ -1,89 +1,518 @@
-use std::ops::Deref;
+use std::{io::Read, ops::Deref, path::Path, sync::Arc};
 
-use arrow::{array::RecordBatch, util::pretty::pretty_format_batches};
-use datafusion::prelude::{CsvReadOptions, NdJsonReadOptions, SessionConfig, SessionContext};
+use anyhow::bail;
+use arrow::{
+    array::RecordBatch,
+    csv::reader::Format,
+    datatypes::{DataType, Field, Schema, TimeUnit},
+};
+use bytes::Bytes;
+use datafusion::{
+    catalog::{schema::SchemaProvider, CatalogProvider, MemorySchemaProvider},
+    datasource::{
+        file_format::file_compression_type::FileCompressionType, MemTable, TableProvider,
+    },
+    execution::{
+        options::ArrowReadOptions,
+        runtime_env::{RuntimeConfig, RuntimeEnv},
+    },
+    functions::expr_fn::nullif,
+    prelude::{
+        cast, ident, lit, AvroReadOptions, CsvReadOptions, NdJsonReadOptions, ParquetReadOptions,
+        SessionConfig, SessionContext,
+    },
+};
+use futures::StreamExt;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 
 use crate::{
-    cli::{ConnectOpts, DatasetConn},
-    Backend, ReplDisplay,
+    backend::Histogram,
+    cli::{
+        file_suffix, open_decompressed, ColumnType, ConnectOpts, CsvOpts, DatasetConn,
+        DescribeOpts, FileFormat, FileOpts, HistOpts, Listing, OutputFile,
+    },
+    Backend, Config, DisplayOpts, ReplDisplay, ReplError, ReplExport, ReplOutput, ReplResult,
 };
 
-use super::describe::DataFrameDescriber;
+use super::{
+    describe::DataFrameDescriber,
+    duckdb::DuckDbTableProvider,
+    export::{page_batches, write_batches, BatchWriter},
+    hist::histogram,
+    mysql::MySqlTableProvider,
+    postgres::PgTableProvider,
+    remote::register_store,
+    spreadsheet::read_sheet,
+    sqlite::SqliteTableProvider,
+};
 
 pub struct DataFusionBackend(SessionContext);
 
 impl Backend for DataFusionBackend {
     async fn connect(&mut self, opts: &ConnectOpts) -> anyhow::Result<()> {
         match &opts.conn {
-            DatasetConn::Postgres(_conn_str) => {
-                println!("Postgres connection is not supported yet");
+            DatasetConn::Postgres(conn_str) => {
+                let table = opts
+                    .table
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("Table name is required for postgres"))?;
+                let provider = PgTableProvider::try_new(conn_str, table).await?;
+                self.register_table(&opts.name, Arc::new(provider))?;
             }
+            DatasetConn::MySql(conn_str) => {
+                let table = opts
+                    .table
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("Table name is required for mysql"))?;
+                let provider = MySqlTableProvider::try_new(conn_str, table).await?;
+                self.register_table(&opts.name, Arc::new(provider))?;
+            }
+            // a single file is read whatever its name, hence the empty
+            // extension
             DatasetConn::Csv(file_opts) => {
-                let csv_opts = CsvReadOptions {
-                    file_extension: &file_opts.ext,
-                    file_compression_type: file_opts.compression,
-                    ..Default::default()
-                };
-                self.register_csv(&opts.name, &file_opts.filename, csv_opts)
+                self.register_csv_files(&opts.name, file_opts, "", &[], &opts.csv)
                     .await?;
             }
-            DatasetConn::Parquet(filename) => {
-                self.register_parquet(&opts.name, filename, Default::default())
+            DatasetConn::Parquet(file_opts)
+                if file_opts.compression != FileCompressionType::UNCOMPRESSED =>
+            {
+                self.register_compressed_parquet(&opts.name, file_opts)?;
+            }
+            DatasetConn::Parquet(file_opts) => {
+                let parquet_opts = ParquetReadOptions {
+                    file_extension: "",
+                    ..Default::default()
+                };
+                self.register_parquet(&opts.name, &file_opts.filename, parquet_opts)
                     .await?;
             }
             DatasetConn::NdJson(file_opts) => {
                 let json_opts = NdJsonReadOptions {
-                    file_extension: &file_opts.ext,
+                    file_extension: "",
                     file_compression_type: file_opts.compression,
                     ..Default::default()
                 };
                 self.register_json(&opts.name, &file_opts.filename, json_opts)
                     .await?;
             }
+            DatasetConn::Arrow(file_opts) => {
+                ensure_uncompressed(file_opts)?;
+                let arrow_opts = ArrowReadOptions {
+                    file_extension: "",
+                    ..Default::default()
+                };
+                self.register_arrow(&opts.name, &file_opts.filename, arrow_opts)
+                    .await?;
+            }
+            DatasetConn::Spreadsheet(file_opts) => {
+                ensure_uncompressed(file_opts)?;
+                let batch = read_sheet(
+                    &file_opts.filename,
+                    &file_opts.ext,
+                    opts.sheet.as_deref(),
+                    opts.csv.no_header,
+                )?;
+                let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+                self.register_table(&opts.name, Arc::new(table))?;
+            }
+            DatasetConn::Sqlite(file_opts) => {
+                ensure_uncompressed(file_opts)?;
+                let path = &file_opts.filename;
+                self.register_database(
+                    &opts.name,
+                    opts.table.as_deref(),
+                    || SqliteTableProvider::tables(path),
+                    |table| SqliteTableProvider::try_new(path, table),
+                )?;
+            }
+            DatasetConn::DuckDb(file_opts) => {
+                ensure_uncompressed(file_opts)?;
+                let path = &file_opts.filename;
+                self.register_database(
+                    &opts.name,
+                    opts.table.as_deref(),
+                    || DuckDbTableProvider::tables(path),
+                    |table| DuckDbTableProvider::try_new(path, table),
+                )?;
+            }
+            DatasetConn::Avro(file_opts) => {
+                ensure_uncompressed(file_opts)?;
+                let avro_opts = AvroReadOptions {
+                    file_extension: "",
+                    ..Default::default()
+                };
+                self.register_avro(&opts.name, &file_opts.filename, avro_opts)
+                    .await?;
+            }
+            DatasetConn::Listing(path) => {
+                let compression = opts.compression.map(Into::into);
+                let listing = Listing::resolve(path, opts.format, compression)?;
+                self.register_listing(&opts.name, &listing, &opts.csv)
+                    .await?;
+            }
+            DatasetConn::Remote(url) => {
+                register_store(&self.0, url, &opts.remote)?;
+                let compression = opts.compression.map(Into::into);
+                let listing = Listing::remote(url, opts.format, compression)?;
+                self.register_listing(&opts.name, &listing, &opts.csv)
+                    .await?;
+            }
+            DatasetConn::File(path) => bail!("Cannot tell the format of {}, use --format", path),
         }
         Ok(())
     }
-    async fn list(&self) -> anyhow::Result<impl ReplDisplay> {
-        let sql = "select table_name, table_type from information_schema.tables where table_schema = 'public'";
-        let df = self.sql(sql).await?;
-        Ok(df)
+    async fn disconnect(&mut self, name: &str) -> anyhow::Result<()> {
+        if self.0.deregister_table(name)?.is_none() {
+            // databases connected as a whole are schemas
+            self.default_catalog()?.deregister_schema(name, true)?;
+        }
+        Ok(())
     }
-    async fn describe(&self, name: &str) -> anyhow::Result<impl ReplDisplay> {
+    async fn list(&self) -> anyhow::Result<ReplResult> {
+        let sql = "select case when table_schema = 'public' then table_name else table_schema || '.' || table_name end as table_name, table_type from information_schema.tables where table_schema <> 'information_schema' order by table_schema <> 'public', table_name";
+        let df = self.0.sql(sql).await?;
+        Ok(df.into())
+    }
+    async fn describe(&self, opts: &DescribeOpts) -> anyhow::Result<ReplResult> {
+        self.ensure_table(&opts.name)?;
         let df = self
             .0
-            .sql(format!("select * from {}", name).as_str())
+            .sql(format!("select * from {}", opts.name).as_str())
             .await?;
-        let ddf = DataFrameDescriber::try_new(df)?;
-        ddf.describe()
+        let ddf = DataFrameDescriber::try_new(df, opts.methods(), opts.by.clone())?;
+        Ok(ddf.describe().await?.into())
     }
-    async fn schema(&self, name: &str) -> anyhow::Result<impl ReplDisplay> {
+    async fn schema(&self, name: &str) -> anyhow::Result<ReplResult> {
+        self.ensure_table(name)?;
         let df = self.0.sql(format!("DESCRIBE {}", name).as_str()).await?;
-        Ok(df)
+        Ok(df.into())
     }
-    async fn head(&self, name: &str, n: usize) -> anyhow::Result<impl ReplDisplay> {
+    async fn head(&self, name: &str, n: usize) -> anyhow::Result<ReplResult> {
+        self.ensure_table(name)?;
         let df = self
             .0
             .sql(format!("SELECT * FROM {} LIMIT {}", name, n).as_str())
             .await?;
-        Ok(df)
+        Ok(df.into())
+    }
+    async fn hist(&self, opts: &HistOpts) -> anyhow::Result<Histogram> {
+        self.ensure_table(&opts.name)?;
+        let df = self.0.table(opts.name.as_str()).await?;
+        histogram(df, &opts.column, opts.bins, opts.method).await
     }
-    async fn sql(&self, query: &str) -> anyhow::Result<impl ReplDisplay> {
+    async fn sql(&self, query: &str) -> anyhow::Result<ReplResult> {
         let df = self.0.sql(query).await?;
-        Ok(df)
+        Ok(df.into())
     }
 }
 
 impl Default for DataFusionBackend {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
 
 impl DataFusionBackend {
-    pub fn new() -> Self {
-        let mut config = SessionConfig::new();
-        config.options_mut().catalog.information_schema = true;
-        Self(SessionContext::new_with_config(config))
+    pub fn new(config: &Config) -> Self {
+        let mut session = SessionConfig::new().with_batch_size(config.batch_size);
+        if config.target_partitions > 0 {
+            session = session.with_target_partitions(config.target_partitions);
+        }
+        session.options_mut().catalog.information_schema = true;
+        let mut runtime = RuntimeConfig::new();
+        if let Some(limit) = config.memory_limit() {
+            runtime = runtime.with_memory_limit(limit, 1.0);
+        }
+        let runtime = RuntimeEnv::new(runtime).expect("Failed to create DataFusion runtime");
+        Self(SessionContext::new_with_config_rt(
+            session,
+            Arc::new(runtime),
+        ))
+    }
+
+    /// Registers the files of a directory or glob, their partition
+    /// directories read as string columns.
+    async fn register_listing(
+        &self,
+        name: &str,
+        listing: &Listing,
+        csv: &CsvOpts,
+    ) -> anyhow::Result<()> {
+        let partitions = listing
+            .partitions
+            .iter()
+            .map(|p| (p.clone(), DataType::Utf8))
+            .collect::<Vec<_>>();
+        let suffix = file_suffix(&listing.ext, listing.compression);
+        match listing.format {
+            FileFormat::Csv => {
+                let files = FileOpts {
+                    filename: listing.path.clone(),
+                    ext: listing.ext.clone(),
+                    compression: listing.compression,
+                };
+                self.register_csv_files(name, &files, &suffix, &listing.partitions, csv)
+                    .await?;
+            }
+            FileFormat::Parquet if listing.compression != FileCompressionType::UNCOMPRESSED => {
+                bail!("Compressed parquet files can only be connected one at a time")
+            }
+            FileFormat::Parquet => {
+                let parquet_opts = ParquetReadOptions {
+                    file_extension: &suffix,
+                    table_partition_cols: partitions,
+                    ..Default::default()
+                };
+                self.register_parquet(name, &listing.path, parquet_opts)
+                    .await?;
+            }
+            FileFormat::NdJson => {
+                let json_opts = NdJsonReadOptions {
+                    file_extension: &suffix,
+                    file_compression_type: listing.compression,
+                    table_partition_cols: partitions,
+                    ..Default::default()
+                };
+                self.register_json(name, &listing.path, json_opts).await?;
+            }
+            _ if listing.compression != FileCompressionType::UNCOMPRESSED => {
+                bail!("Compressed {} files are not supported", listing.format)
+            }
+            FileFormat::Arrow => {
+                let arrow_opts = ArrowReadOptions {
+                    file_extension: &suffix,
+                    table_partition_cols: partitions,
+                    ..Default::default()
+                };
+                self.register_arrow(name, &listing.path, arrow_opts).await?;
+            }
+            FileFormat::Avro => {
+                let avro_opts = AvroReadOptions {
+                    file_extension: &suffix,
+                    table_partition_cols: partitions,
+                    ..Default::default()
+                };
+                self.register_avro(name, &listing.path, avro_opts).await?;
+            }
+            FileFormat::Spreadsheet | FileFormat::Sqlite | FileFormat::DuckDb => {
+                bail!(
+                    "{} files can only be connected one at a time",
+                    listing.format
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers CSV files read with `csv`: the columns it lists get their
+    /// type, and its null value is read as null.
+    async fn register_csv_files(
+        &self,
+        name: &str,
+        files: &FileOpts,
+        suffix: &str,
+        partitions: &[String],
+        csv: &CsvOpts,
+    ) -> anyhow::Result<()> {
+        let path = &files.filename;
+        let read_opts = |schema| csv_read_options(csv, files, suffix, partitions, schema);
+        let quoted = infer_quoted_csv(csv, files)?;
+        if csv.schema.is_none() && csv.null_value.is_none() {
+            self.register_csv(name, path, read_opts(quoted.as_ref()))
+                .await?;
+            return Ok(());
+        }
+
+        let inferred = match quoted {
+            Some(schema) => schema,
+            None => self
+                .read_csv(path, read_opts(None))
+                .await?
+                .schema()
+                .as_arrow()
+                .clone(),
+        };
+        let fields = inferred
+            .fields()
+            .iter()
+            .filter(|f| !partitions.contains(f.name()))
+            .map(|f| {
+                let dt = match csv.schema.as_ref().and_then(|s| s.get(f.name())) {
+                    Some(t) => arrow_type(t),
+                    None => f.data_type().clone(),
+                };
+                Field::new(f.name(), dt, true)
+            })
+            .collect::<Vec<_>>();
+        if let Some(schema) = &csv.schema {
+            if let Some((column, _)) = schema
+                .0
+                .iter()
+                .find(|(n, _)| !fields.iter().any(|f| f.name() == n))
+            {
+                bail!("Column not found: {}", column);
+            }
+        }
+        let schema = Schema::new(fields);
+        let Some(null) = &csv.null_value else {
+            self.register_csv(name, path, read_opts(Some(&schema)))
+                .await?;
+            return Ok(());
+        };
+
+        // read every column as a string to replace the null value before
+        // casting it to its type
+        let raw = Schema::new(
+            schema
+                .fields()
+                .iter()
+                .map(|f| Field::new(f.name(), DataType::Utf8, true))
+                .collect::<Vec<_>>(),
+        );
+        let mut columns = schema
+            .fields()
+            .iter()
+            .map(|f| {
+                cast(
+                    nullif(ident(f.name()), lit(null.as_str())),
+                    f.data_type().clone(),
+                )
+                .alias(f.name())
+            })
+            .collect::<Vec<_>>();
+        columns.extend(partitions.iter().map(ident));
+        let df = self
+            .read_csv(path, read_opts(Some(&raw)))
+            .await?
+            .select(columns)?;
+        self.register_table(name, df.into_view())?;
+        Ok(())
+    }
+
+    /// Registers one table of a database as `name`, or all of them in a
+    /// schema `name`, queried as `name.table`.
+    fn register_database<T: TableProvider + 'static>(
+        &self,
+        name: &str,
+        table: Option<&str>,
+        tables: impl FnOnce() -> anyhow::Result<Vec<String>>,
+        open: impl Fn(&str) -> anyhow::Result<T>,
+    ) -> anyhow::Result<()> {
+        if let Some(table) = table {
+            self.register_table(name, Arc::new(open(table)?))?;
+            return Ok(());
+        }
+        let schema = MemorySchemaProvider::new();
+        for table in tables()? {
+            let provider = open(&table)?;
+            schema.register_table(table, Arc::new(provider))?;
+        }
+        self.default_catalog()?
+            .register_schema(name, Arc::new(schema))?;
+        Ok(())
+    }
+
+    fn default_catalog(&self) -> anyhow::Result<Arc<dyn CatalogProvider>> {
+        let name = self
+            .0
+            .copied_config()
+            .options()
+            .catalog
+            .default_catalog
+            .clone();
+        self.0
+            .catalog(&name)
+            .ok_or_else(|| anyhow::anyhow!("Catalog {} not found", name))
+    }
+
+    /// Parquet needs random access, so a compressed file is decompressed in
+    /// memory.
+    fn register_compressed_parquet(&self, name: &str, file: &FileOpts) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        open_decompressed(&file.filename, file.compression)?.read_to_end(&mut buf)?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buf))?;
+        let schema = builder.schema().clone();
+        let batches = builder.build()?.collect::<Result<Vec<_>, _>>()?;
+        let table = MemTable::try_new(schema, vec![batches])?;
+        self.register_table(name, Arc::new(table))?;
+        Ok(())
+    }
+
+    fn ensure_table(&self, name: &str) -> anyhow::Result<()> {
+        if !self.0.table_exist(name)? {
+            return Err(ReplError::UnknownDataset(name.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Arrow, Avro, spreadsheet and database files are compressed internally, if
+/// at all.
+fn ensure_uncompressed(file: &FileOpts) -> anyhow::Result<()> {
+    if file.compression != FileCompressionType::UNCOMPRESSED {
+        bail!("Compressed {} files are not supported", file.ext);
+    }
+    Ok(())
+}
+
+fn csv_read_options<'a>(
+    csv: &CsvOpts,
+    files: &FileOpts,
+    suffix: &'a str,
+    partitions: &[String],
+    schema: Option<&'a Schema>,
+) -> CsvReadOptions<'a> {
+    let defaults = CsvReadOptions::default();
+    CsvReadOptions {
+        has_header: !csv.no_header,
+        delimiter: csv.delimiter(&files.ext),
+        quote: csv.quote.unwrap_or(defaults.quote),
+        escape: csv.escape,
+        schema,
+        schema_infer_max_records: csv.infer_rows.unwrap_or(defaults.schema_infer_max_records),
+        file_extension: suffix,
+        table_partition_cols: partitions
+            .iter()
+            .map(|p| (p.clone(), DataType::Utf8))
+            .collect(),
+        file_compression_type: files.compression,
+        ..defaults
+    }
+}
+
+/// DataFusion infers the types of CSV files ignoring their quote and escape
+/// characters, so a single local file read with other ones is inferred here.
+fn infer_quoted_csv(csv: &CsvOpts, files: &FileOpts) -> anyhow::Result<Option<Schema>> {
+    if (csv.quote.is_none() && csv.escape.is_none()) || !Path::new(&files.filename).is_file() {
+        return Ok(None);
+    }
+    let mut format = Format::default()
+        .with_header(!csv.no_header)
+        .with_delimiter(csv.delimiter(&files.ext));
+    if let Some(quote) = csv.quote {
+        format = format.with_quote(quote);
+    }
+    if let Some(escape) = csv.escape {
+        format = format.with_escape(escape);
+    }
+    let rows = csv
+        .infer_rows
+        .unwrap_or(CsvReadOptions::default().schema_infer_max_records);
+    let reader = open_decompressed(&files.filename, files.compression)?;
+    let (schema, _) = format.infer_schema(reader, Some(rows))?;
+    Ok(Some(schema))
+}
+
+fn arrow_type(t: ColumnType) -> DataType {
+    match t {
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Int32 => DataType::Int32,
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::Float32 => DataType::Float32,
+        ColumnType::Float64 => DataType::Float64,
+        ColumnType::Utf8 => DataType::Utf8,
+        ColumnType::Date => DataType::Date32,
+        ColumnType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
     }
 }
 
@@ -96,16 +525,209 @@ impl Deref for DataFusionBackend {
 }
 
 impl ReplDisplay for datafusion::dataframe::DataFrame {
-    async fn display(self) -> anyhow::Result<String> {
-        let barches = self.collect().await?;
-        let data = pretty_format_batches(&barches)?;
-        Ok(data.to_string())
+    /// Streams the result, keeping at most `max_rows` rows of it. The rest is
+    /// only counted.
+    async fn display(self, opts: &DisplayOpts) -> anyhow::Result<ReplOutput> {
+        let schema = Arc::new(self.schema().as_arrow().clone());
+        let mut stream = self.execute_stream().await?;
+        let cap = opts.row_cap();
+        let (mut total, mut shown) = (0, 0);
+        let mut batches = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            total += batch.num_rows();
+            if shown < cap {
+                let batch = batch.slice(0, batch.num_rows().min(cap - shown));
+                shown += batch.num_rows();
+                batches.push(batch);
+            }
+        }
+        page_batches(schema, &batches, total, opts)
     }
 }
 
 impl ReplDisplay for RecordBatch {
-    async fn display(self) -> anyhow::Result<String> {
-        let data = pretty_format_batches(&[self])?;
-        Ok(data.to_string())
+    async fn display(self, opts: &DisplayOpts) -> anyhow::Result<ReplOutput> {
+        let total = self.num_rows();
+        let batch = self.slice(0, total.min(opts.row_cap()));
+        page_batches(self.schema(), &[batch], total, opts)
+    }
+}
+
+impl ReplExport for datafusion::dataframe::DataFrame {
+    async fn export(self, output: &OutputFile) -> anyhow::Result<String> {
+        let schema = Arc::new(self.schema().as_arrow().clone());
+        let mut stream = self.execute_stream().await?;
+        let mut writer = BatchWriter::try_new(schema, output)?;
+        while let Some(batch) = stream.next().await {
+            writer.write(&batch?)?;
+        }
+        let rows = writer.finish()?;
+        Ok(format!("Wrote {} rows to {}", rows, output.path))
+    }
+}
+
+impl ReplExport for RecordBatch {
+    async fn export(self, output: &OutputFile) -> anyhow::Result<String> {
+        let schema = self.schema();
+        write_batches(&[self], schema, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use arrow::{
+        array::{AsArray, Int64Array, StringArray},
+        ipc::writer::FileWriter,
+    };
+    use clap::Parser;
+
+    use super::*;
+
+    async fn connect(path: &str, args: &[&str]) -> anyhow::Result<DataFusionBackend> {
+        let mut backend = DataFusionBackend::new(&Config::default());
+        let opts = ConnectOpts::try_parse_from(["connect", path, "-n", "t"].iter().chain(args))?;
+        backend.connect(&opts.resolve()?).await?;
+        Ok(backend)
+    }
+
+    #[tokio::test]
+    async fn csv_options_should_be_applied() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("data.csv");
+        fs::write(&path, "1;'a;b'\n2;'c'\n")?;
+        let path = path.to_string_lossy();
+
+        let args = ["--delimiter", ";", "--quote", "'", "--no-header"];
+        let backend = connect(&path, &args).await?;
+        let batches = backend
+            .0
+            .sql("select column_1, column_2 from t order by column_1")
+            .await?
+            .collect()
+            .await?;
+        let names = batches[0].column(1).as_string::<i32>();
+        assert_eq!(names.iter().collect::<Vec<_>>(), [Some("a;b"), Some("c")]);
+
+        // read with the defaults, the first row is the header
+        let backend = connect(&path, &[]).await?;
+        let df = backend.table("t").await?;
+        assert_eq!(df.schema().fields().len(), 1);
+        assert_eq!(df.count().await?, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn csv_schema_and_null_value_should_be_applied() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("data.tsv");
+        fs::write(&path, "id\tscore\n1\tNA\n2\t3.5\n")?;
+        let path = path.to_string_lossy();
+
+        let args = ["--null-value", "NA", "--schema", "id:int64,score:double"];
+        let backend = connect(&path, &args).await?;
+        let df = backend.table("t").await?;
+        let types = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(types, [DataType::Int64, DataType::Float64]);
+        assert_eq!(df.filter(ident("score").is_null())?.count().await?, 1);
+
+        let args = ["--schema", "missing:int"];
+        assert!(connect(&path, &args).await.is_err());
+        Ok(())
+    }
+
+    /// Connects `path` as `t`, then checks its rows through sql and head.
+    async fn assert_round_trip(path: &str) -> anyhow::Result<()> {
+        let backend = connect(path, &[]).await?;
+        let batches = backend
+            .0
+            .sql("select name from t where id = 2")
+            .await?
+            .collect()
+            .await?;
+        let names = batches[0].column(0).as_string::<i32>();
+        assert_eq!(names.iter().collect::<Vec<_>>(), [Some("b")]);
+
+        let ReplResult::DataFusion(df) = backend.head("t", 1).await? else {
+            bail!("expected a DataFusion result");
+        };
+        let rows = df
+            .collect()
+            .await?
+            .iter()
+            .map(|b| b.num_rows())
+            .sum::<usize>();
+        assert_eq!(rows, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn arrow_files_should_be_connected() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("data.arrow");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+            ],
+        )?;
+        let mut writer = FileWriter::try_new(fs::File::create(&path)?, &schema)?;
+        writer.write(&batch)?;
+        writer.finish()?;
+
+        assert_round_trip(&path.to_string_lossy()).await
+    }
+
+    #[tokio::test]
+    async fn avro_files_should_be_connected() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("data.avro");
+        let schema = apache_avro::Schema::parse_str(
+            r#"{"type": "record", "name": "row", "fields": [
+                {"name": "id", "type": "long"},
+                {"name": "name", "type": "string"}
+            ]}"#,
+        )?;
+        let mut writer = apache_avro::Writer::new(&schema, Vec::new());
+        for (id, name) in [(1i64, "a"), (2, "b")] {
+            let mut record = apache_avro::types::Record::new(&schema).unwrap();
+            record.put("id", id);
+            record.put("name", name);
+            writer.append(record)?;
+        }
+        fs::write(&path, writer.into_inner()?)?;
+
+        assert_round_trip(&path.to_string_lossy()).await
+    }
+
+    #[tokio::test]
+    async fn compressed_binary_files_should_be_rejected() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        for name in ["data.arrow.gz", "data.avro.zst"] {
+            let path = dir.path().join(name);
+            fs::write(&path, "")?;
+            let Err(err) = connect(&path.to_string_lossy(), &[]).await else {
+                bail!("{} should be rejected", name);
+            };
+            assert!(
+                err.to_string().starts_with("Compressed"),
+                "{}: {}",
+                name,
+                err
+            );
+        }
+        Ok(())
     }
 }
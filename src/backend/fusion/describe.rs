@@ -1,166 +1,693 @@
-use std::fmt;
+//! `describe` for DataFusion data frames.
+//!
+//! The output has the group by columns if any, a `stats` column (Utf8) naming
+//! the statistic, then one column per other input column, with the rows of
+//! every group in the order the statistics were requested.
+//! Strings and binaries are described by their length, lists by their number
+//! of elements and booleans as 0 or 1, all as Float64, but the mode is their
+//! most frequent value, so a column whose mode is requested is Utf8. Temporal
+//! columns keep their own type when any statistic is a value of the column,
+//! as min, max or mean are, the others being held as the integers under it
+//! (a count of 100 is the 100th unit after the epoch), and are Float64 when
+//! none is.
 
-use arrow::datatypes::DataType;
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use arrow::datatypes::{DataType, Field};
 use datafusion::{
-    functions_aggregate::{count::count, expr_fn::avg, median::median, stddev::stddev, sum::sum},
-    prelude::{array_length, case, cast, col, is_null, length, lit, max, min, DataFrame},
+    common::{Column, JoinType},
+    functions_aggregate::{
+        count::{count, count_distinct},
+        expr_fn::{approx_percentile_cont, avg, first_value},
+        median::median,
+        stddev::stddev,
+        sum::sum,
+    },
+    logical_expr::LogicalPlanBuilder,
+    prelude::{
+        array_length, case, cast, coalesce, col, encode, ident, is_null, length, lit, max, min,
+        power, DataFrame, Expr,
+    },
 };
 
-#[allow(unused)]
-#[derive(Debug)]
-pub enum DescribeMethod {
-    Total,
-    NullTotal,
-    Mean,
-    Stddev,
-    Min,
-    Max,
-    Median,
-    Percentile(u8),
-}
+use crate::backend::DescribeMethod;
 
-#[allow(unused)]
 pub struct DataFrameDescriber {
     original: DataFrame,
-    transformed: DataFrame,
+    /// Columns the statistics are grouped by, empty to describe the whole
+    /// data frame.
+    by: Vec<String>,
+    /// Every other column with the expression that maps it to a number.
+    columns: Vec<(Field, Expr)>,
     methods: Vec<DescribeMethod>,
 }
 
 impl DataFrameDescriber {
-    pub fn try_new(df: DataFrame) -> anyhow::Result<Self> {
-        let fields = df.schema().fields().iter();
-        let expressions = fields
-            .map(|field| {
-                let dt = field.data_type();
-                let expr = match dt {
-                    dt if dt.is_temporal() => cast(col(field.name()), DataType::Float64),
-                    dt if dt.is_numeric() => col(field.name()),
-                    DataType::List(_) | DataType::LargeList(_) => array_length(col(field.name())),
-                    _ => length(cast(col(field.name()), DataType::Utf8)),
-                };
-                expr.alias(field.name())
-            })
+    pub fn try_new(
+        df: DataFrame,
+        methods: Vec<DescribeMethod>,
+        by: Vec<String>,
+    ) -> anyhow::Result<Self> {
+        for name in &by {
+            df.schema()
+                .field_with_unqualified_name(name)
+                .map_err(|_| anyhow!("Group by column not found: {}", name))?;
+        }
+        let columns = df
+            .schema()
+            .fields()
+            .iter()
+            .filter(|field| !by.contains(field.name()))
+            .map(|field| (field.as_ref().clone(), transform(field)))
             .collect::<Vec<_>>();
-        let transformed = df.clone().select(expressions)?;
         Ok(Self {
             original: df,
-            transformed,
-            methods: vec![
-                DescribeMethod::Total,
-                DescribeMethod::NullTotal,
-                DescribeMethod::Mean,
-                DescribeMethod::Stddev,
-                DescribeMethod::Min,
-                DescribeMethod::Max,
-                DescribeMethod::Median,
-            ],
+            by,
+            columns,
+            methods,
         })
     }
 
-    pub fn describe(&self) -> anyhow::Result<DataFrame> {
-        let df = self.do_describe()?;
-        let ret = self.cast_back(df)?;
+    pub async fn describe(&self) -> anyhow::Result<DataFrame> {
+        let df = self.do_describe().await?;
+        let ret = self.sort(df)?;
         Ok(ret)
     }
-    fn do_describe(&self) -> anyhow::Result<DataFrame> {
-        let df: Option<DataFrame> = self.methods.iter().fold(None, |acc, method| {
-            let df = self.transformed.clone();
-            let stat_df = match method {
-                DescribeMethod::Total => total(df).unwrap(),
-                DescribeMethod::NullTotal => null_total(df).unwrap(),
-                DescribeMethod::Mean => mean(df).unwrap(),
-                DescribeMethod::Stddev => std_div(df).unwrap(),
-                DescribeMethod::Min => minimum(df).unwrap(),
-                DescribeMethod::Max => maximum(df).unwrap(),
-                DescribeMethod::Median => med(df).unwrap(),
-                // TODO implement percentile
-                DescribeMethod::Percentile(_) => todo!(),
-            };
-            let stat_df = stat_df
-                .with_column("stats", lit(method.to_string()))
-                .unwrap();
-            match acc {
-                Some(acc) => Some(acc.union(stat_df).unwrap()),
-                None => Some(stat_df),
-            }
-        });
-        Ok(df.unwrap())
-    }
-    fn cast_back(&self, df: DataFrame) -> anyhow::Result<DataFrame> {
-        let fields = self.original.schema().fields().iter();
-        let mut all_expressions = vec![col("stats")];
-        let mut expressions = fields
-            .map(|field| {
-                let dt = field.data_type();
-                let expr = match dt {
-                    dt if dt.is_temporal() => cast(col(field.name()), dt.clone()),
-                    dt if dt.is_numeric() => col(field.name()),
-                    DataType::List(_) | DataType::LargeList(_) => {
-                        cast(col(field.name()), DataType::Int32)
+
+    /// Computes all the requested statistics in a single aggregation over the
+    /// data (one row per group), then pivots every row into one row per
+    /// statistic. Only `Mode`, which groups by every value, and skewness and
+    /// kurtosis, which need the mean first, take extra passes.
+    async fn do_describe(&self) -> anyhow::Result<DataFrame> {
+        let mut seen = HashSet::new();
+        let mut aggregates = vec![count(lit(1)).alias("rows")];
+        for method in &self.methods {
+            for (i, (field, expr)) in self.columns.iter().enumerate() {
+                for (alias, agg) in aggregates_for(*method, i, field.name(), expr)? {
+                    if seen.insert(alias.clone()) {
+                        aggregates.push(agg.alias(alias));
                     }
-                    _ => cast(col(field.name()), DataType::UInt32),
-                };
-                expr.alias(field.name())
+                }
+            }
+        }
+        let mut stats = self
+            .original
+            .clone()
+            .aggregate(self.group_exprs(), aggregates)?;
+        let with_mode = self
+            .methods
+            .iter()
+            .any(|m| matches!(m, DescribeMethod::Mode | DescribeMethod::ModeFreq));
+        if with_mode {
+            for (i, (field, _)) in self.columns.iter().enumerate() {
+                stats = self.join_mode(stats, i, field)?;
+            }
+        }
+        let order = self
+            .methods
+            .iter()
+            .map(|m| match m {
+                DescribeMethod::Skewness => 3,
+                DescribeMethod::Kurtosis => 4,
+                _ => 0,
             })
+            .max()
+            .unwrap_or(0);
+        if order > 0 {
+            stats = self.join_moments(stats, order)?;
+        }
+        let stats = stats.cache().await?;
+
+        let mut ret: Option<DataFrame> = None;
+        for (order, method) in self.methods.iter().enumerate() {
+            let mut expressions = self.group_exprs();
+            expressions.extend(
+                self.columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (field, _))| self.output(*method, i, field).alias(field.name())),
+            );
+            let stat_df = stats
+                .clone()
+                .select(expressions)?
+                .with_column("stats", lit(method.to_string()))?
+                .with_column("order", lit(order as u32))?;
+            ret = Some(match ret {
+                Some(acc) => acc.union(stat_df)?,
+                None => stat_df,
+            });
+        }
+        ret.ok_or_else(|| anyhow!("No statistics to describe"))
+    }
+
+    /// Adds the most frequent non-null value of the `i`th column (and its
+    /// frequency) of every group to `stats`, ties broken by the smallest value.
+    fn join_mode(&self, stats: DataFrame, i: usize, field: &Field) -> anyhow::Result<DataFrame> {
+        let keys = self.keys(&i.to_string());
+        let mut value_group = self.group_as(&keys);
+        value_group.push(mode_value(field).alias("value"));
+        let order = Some(vec![
+            col("freq").sort(false, false),
+            col("value").sort(true, false),
+        ]);
+        let top = self
+            .original
+            .clone()
+            .aggregate(value_group, vec![count(lit(1)).alias("freq")])?
+            .filter(col("value").is_not_null())?
+            .aggregate(
+                keys.iter().map(ident).collect(),
+                vec![
+                    first_value(col("value"), order.clone())
+                        .alias(agg_name(DescribeMethod::Mode, i)),
+                    first_value(col("freq"), order).alias(agg_name(DescribeMethod::ModeFreq, i)),
+                ],
+            )?;
+        // when grouped, groups whose values are all null have no mode
+        self.join_groups(stats, top, &keys)
+    }
+
+    /// Adds the central moments of every column up to `order`, from the 2nd,
+    /// to `stats`. Means come from a first pass, as moments derived from raw
+    /// ones lose all precision when the mean is large against the spread.
+    fn join_moments(&self, stats: DataFrame, order: usize) -> anyhow::Result<DataFrame> {
+        let keys = self.keys("m");
+        let means = self.original.clone().aggregate(
+            self.group_as(&keys),
+            self.columns
+                .iter()
+                .enumerate()
+                .map(|(i, (_, x))| avg(cast(x.clone(), DataType::Float64)).alias(mean_name(i)))
+                .collect(),
+        )?;
+        let mut aggregates = vec![];
+        for (i, (_, x)) in self.columns.iter().enumerate() {
+            let d = cast(x.clone(), DataType::Float64) - ident(mean_name(i));
+            for k in 2..=order {
+                let agg = avg(power(d.clone(), lit(k as f64)));
+                aggregates.push(agg.alias(agg_name(format!("m{}", k), i)));
+            }
+        }
+        let moments = self
+            .join_groups(self.original.clone(), means, &keys)?
+            .aggregate(keys.iter().map(ident).collect(), aggregates)?;
+        self.join_groups(stats, moments, &keys)
+    }
+
+    /// Names for the group by columns of a data frame joined to the groups.
+    fn keys(&self, suffix: &str) -> Vec<String> {
+        (0..self.by.len())
+            .map(|j| format!("__by{}_{}", j, suffix))
+            .collect()
+    }
+
+    fn group_as(&self, keys: &[String]) -> Vec<Expr> {
+        self.by
+            .iter()
+            .zip(keys)
+            .map(|(name, key)| ident(name).alias(key))
+            .collect()
+    }
+
+    /// Left joins `right`, with its group by columns named `keys`, to the
+    /// groups of `left`.
+    fn join_groups(
+        &self,
+        left: DataFrame,
+        right: DataFrame,
+        keys: &[String],
+    ) -> anyhow::Result<DataFrame> {
+        let (state, plan) = left.into_parts();
+        let builder = LogicalPlanBuilder::from(plan);
+        let right = right.into_unoptimized_plan();
+        let builder = if self.by.is_empty() {
+            builder.cross_join(right)?
+        } else {
+            let left_keys = self.by.iter().map(Column::from_name).collect::<Vec<_>>();
+            let right_keys = keys.iter().map(Column::from_name).collect::<Vec<_>>();
+            builder.join_detailed(right, JoinType::Left, (left_keys, right_keys), None, true)?
+        };
+        Ok(DataFrame::new(state, builder.build()?))
+    }
+
+    fn group_exprs(&self) -> Vec<Expr> {
+        self.by.iter().map(ident).collect()
+    }
+
+    /// The `method` row of the `i`th column, typed as the column's output.
+    fn output(&self, method: DescribeMethod, i: usize, field: &Field) -> Expr {
+        let stat = pivot(method, i);
+        let dt = field.data_type();
+        match method {
+            // the mode is already a value of the column
+            DescribeMethod::Mode if self.keeps_type(field) => stat,
+            // counts and spreads are held as the integers under the type,
+            // as arrow only casts integers to every temporal type
+            _ if self.keeps_type(field) => cast(cast(stat, DataType::Int64), dt.clone()),
+            DescribeMethod::Mode if self.is_text(field) => cast(stat, DataType::Utf8),
+            _ if self.is_text(field) => cast(stat, DataType::Utf8),
+            _ => cast(stat, DataType::Float64),
+        }
+    }
+
+    /// Whether the statistics of a temporal column keep its type: when any
+    /// of them is a value of the column, as min, max or mean are.
+    fn keeps_type(&self, field: &Field) -> bool {
+        field.data_type().is_temporal() && self.methods.iter().any(|m| is_value(*m))
+    }
+
+    /// Whether the statistics of a column are shown as text: when the mode
+    /// of a column that isn't a number is requested.
+    fn is_text(&self, field: &Field) -> bool {
+        let dt = field.data_type();
+        !dt.is_temporal()
+            && !dt.is_numeric()
+            && *dt != DataType::Boolean
+            && self.methods.contains(&DescribeMethod::Mode)
+    }
+
+    /// Orders the rows by group then by the requested statistic order.
+    fn sort(&self, df: DataFrame) -> anyhow::Result<DataFrame> {
+        let mut expressions = self.group_exprs();
+        expressions.push(col("stats"));
+        expressions.extend(self.columns.iter().map(|(field, _)| ident(field.name())));
+        let mut sort = self
+            .by
+            .iter()
+            .map(|name| ident(name).sort(true, false))
             .collect::<Vec<_>>();
-        all_expressions.append(&mut expressions);
-        Ok(df
-            .select(all_expressions)?
-            .sort(vec![col("stats").sort(true, true)])?)
+        sort.push(col("order").sort(true, true));
+        Ok(df.sort(sort)?.select(expressions)?)
     }
 }
 
-fn null_total(df: DataFrame) -> anyhow::Result<DataFrame> {
-    let fields = df.schema().fields().iter();
-    let ret = df.clone().aggregate(
-        vec![],
-        fields
-            .map(|f| {
-                sum(case(is_null(col(f.name())))
-                    .when(lit(true), lit(1))
-                    .otherwise(lit(0))
-                    .unwrap())
-                .alias(f.name())
-            })
-            .collect::<Vec<_>>(),
-    )?;
-    Ok(ret)
+pub(super) fn transform(field: &Field) -> Expr {
+    let dt = field.data_type();
+    match dt {
+        // integers rather than floats, which lose the nanoseconds of today
+        dt if dt.is_temporal() => cast(col(field.name()), DataType::Int64),
+        dt if dt.is_numeric() => col(field.name()),
+        DataType::Boolean => cast(col(field.name()), DataType::UInt8),
+        DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) => {
+            array_length(col(field.name()))
+        }
+        // binaries need not be valid UTF-8, so their size comes from the hex form
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+            length(encode(col(field.name()), lit("hex"))) / lit(2)
+        }
+        _ => length(cast(col(field.name()), DataType::Utf8)),
+    }
 }
 
-macro_rules! describe_method {
-    ($name:ident, $method:ident) => {
-        fn $name(df: DataFrame) -> anyhow::Result<DataFrame> {
-            let fields = df.schema().fields().iter();
-            let ret = df.clone().aggregate(
-                vec![],
-                fields
-                    .filter(|f| f.data_type().is_numeric())
-                    .map(|f| $method(col(f.name())).alias(f.name()))
-                    .collect::<Vec<_>>(),
-            )?;
-            Ok(ret)
+/// What the mode of a column is taken on: the column itself, binaries as hex
+/// as they need not be valid UTF-8.
+fn mode_value(field: &Field) -> Expr {
+    match field.data_type() {
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+            encode(ident(field.name()), lit("hex"))
+        }
+        _ => ident(field.name()),
+    }
+}
+
+/// Whether the statistic is a value of the column rather than a count or a
+/// dimensionless number.
+fn is_value(method: DescribeMethod) -> bool {
+    matches!(
+        method,
+        DescribeMethod::Min
+            | DescribeMethod::Max
+            | DescribeMethod::Mean
+            | DescribeMethod::Median
+            | DescribeMethod::Percentile(_)
+            | DescribeMethod::Mode
+    )
+}
+
+fn agg_name(key: impl std::fmt::Display, i: usize) -> String {
+    format!("{}__{}", key, i)
+}
+
+fn mean_name(i: usize) -> String {
+    format!("mean__{}", i)
+}
+
+/// The aggregates `method` needs for the `i`th column, keyed by the name they
+/// are computed under. Skewness and kurtosis come from the central moments
+/// joined afterwards.
+fn aggregates_for(
+    method: DescribeMethod,
+    i: usize,
+    name: &str,
+    x: &Expr,
+) -> anyhow::Result<Vec<(String, Expr)>> {
+    let x = x.clone();
+    let agg = match method {
+        DescribeMethod::Total => count(x),
+        DescribeMethod::NullTotal => {
+            sum(case(is_null(x)).when(lit(true), lit(1)).otherwise(lit(0))?)
         }
+        DescribeMethod::Mean => avg(x),
+        DescribeMethod::Stddev => stddev(x),
+        DescribeMethod::Min => min(x),
+        DescribeMethod::Max => max(x),
+        DescribeMethod::Median => median(x),
+        DescribeMethod::Percentile(p) => approx_percentile_cont(x, lit(p as f64 / 100.0)),
+        // distinct values are counted on the original column, not on its length
+        DescribeMethod::Distinct => count_distinct(col(name)),
+        DescribeMethod::ZeroTotal => sum(case(x.eq(lit(0)))
+            .when(lit(true), lit(1))
+            .otherwise(lit(0))?),
+        DescribeMethod::Mode
+        | DescribeMethod::ModeFreq
+        | DescribeMethod::Skewness
+        | DescribeMethod::Kurtosis => return Ok(vec![]),
     };
+    Ok(vec![(agg_name(method, i), agg)])
+}
+
+/// Turns the aggregated values of the `i`th column back into `method`.
+fn pivot(method: DescribeMethod, i: usize) -> Expr {
+    let m = |k: usize| ident(agg_name(format!("m{}", k), i));
+    match method {
+        // population skewness
+        DescribeMethod::Skewness => m(3) / power(m(2), lit(1.5)),
+        // population excess kurtosis
+        DescribeMethod::Kurtosis => m(4) / power(m(2), lit(2.0)) - lit(3.0),
+        DescribeMethod::ModeFreq => coalesce(vec![ident(agg_name(method, i)), lit(0)]),
+        _ => ident(agg_name(method, i)),
+    }
 }
 
-describe_method!(total, count);
-describe_method!(mean, avg);
-describe_method!(std_div, stddev);
-describe_method!(minimum, min);
-describe_method!(maximum, max);
-describe_method!(med, median);
-
-impl fmt::Display for DescribeMethod {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            DescribeMethod::Total => write!(f, "Total"),
-            DescribeMethod::NullTotal => write!(f, "Null Total"),
-            DescribeMethod::Mean => write!(f, "Mean"),
-            DescribeMethod::Stddev => write!(f, "Stddev"),
-            DescribeMethod::Min => write!(f, "Min"),
-            DescribeMethod::Max => write!(f, "Max"),
-            DescribeMethod::Median => write!(f, "Median"),
-            DescribeMethod::Percentile(p) => write!(f, "Percentile {}", p),
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Array, RecordBatch},
+        datatypes::TimeUnit,
+    };
+    use datafusion::{
+        prelude::{CsvReadOptions, NdJsonReadOptions, SessionContext},
+        scalar::ScalarValue,
+    };
+
+    use super::*;
+
+    async fn context() -> anyhow::Result<SessionContext> {
+        let ctx = SessionContext::new();
+        ctx.register_csv("juventus", "assets/juventus.csv", CsvReadOptions::default())
+            .await?;
+        ctx.register_json(
+            "users",
+            "assets/users.ndjson",
+            NdJsonReadOptions::default().file_extension("ndjson"),
+        )
+        .await?;
+        ctx.register_parquet("sample", "assets/sample.parquet", Default::default())
+            .await?;
+        Ok(ctx)
+    }
+
+    async fn describe(
+        ctx: &SessionContext,
+        query: &str,
+        methods: Vec<DescribeMethod>,
+    ) -> anyhow::Result<RecordBatch> {
+        describe_by(ctx, query, methods, vec![]).await
+    }
+
+    async fn describe_by(
+        ctx: &SessionContext,
+        query: &str,
+        methods: Vec<DescribeMethod>,
+        by: Vec<String>,
+    ) -> anyhow::Result<RecordBatch> {
+        let df = ctx.sql(query).await?;
+        let ret = DataFrameDescriber::try_new(df, methods, by)?
+            .describe()
+            .await?;
+        let batches = ret.collect().await?;
+        Ok(arrow::compute::concat_batches(
+            &batches[0].schema(),
+            &batches,
+        )?)
+    }
+
+    fn value(batch: &RecordBatch, column: &str, row: usize) -> ScalarValue {
+        let array = batch.column_by_name(column).expect("column exists");
+        ScalarValue::try_from_array(array, row).expect("row exists")
+    }
+
+    #[tokio::test]
+    async fn describe_should_have_typed_schema() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let batch = describe(&ctx, "SELECT * FROM juventus", DescribeMethod::defaults()).await?;
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "stats");
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        for field in schema.fields().iter().skip(1) {
+            assert_eq!(field.data_type(), &DataType::Float64, "{}", field.name());
+        }
+        assert_eq!(batch.num_rows(), DescribeMethod::defaults().len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_keep_requested_order() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![
+            DescribeMethod::Max,
+            DescribeMethod::Percentile(25),
+            DescribeMethod::Total,
+            DescribeMethod::Skewness,
+            DescribeMethod::Mode,
+        ];
+        let batch = describe(&ctx, "SELECT kit_number FROM juventus", methods.clone()).await?;
+        for (i, method) in methods.iter().enumerate() {
+            assert_eq!(
+                value(&batch, "stats", i),
+                ScalarValue::from(method.to_string())
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_support_string_and_boolean() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![
+            DescribeMethod::Total,
+            DescribeMethod::Min,
+            DescribeMethod::Max,
+        ];
+        let batch = describe(
+            &ctx,
+            "SELECT name, kit_number > 10 AS senior FROM juventus",
+            methods,
+        )
+        .await?;
+        assert_eq!(value(&batch, "name", 0), ScalarValue::Float64(Some(27.0)));
+        assert_eq!(value(&batch, "senior", 0), ScalarValue::Float64(Some(27.0)));
+        assert_eq!(value(&batch, "senior", 1), ScalarValue::Float64(Some(0.0)));
+        assert_eq!(value(&batch, "senior", 2), ScalarValue::Float64(Some(1.0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_measure_binary_by_size() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Max];
+        let batch = describe(
+            &ctx,
+            "SELECT arrow_cast(nationality, 'Binary') AS raw FROM juventus WHERE nationality = 'Italy'",
+            methods,
+        )
+        .await?;
+        assert_eq!(value(&batch, "raw", 0), ScalarValue::Float64(Some(5.0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_measure_list_by_length() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Total, DescribeMethod::Min];
+        let batch = describe(&ctx, "SELECT recent_watched FROM users", methods).await?;
+        assert_eq!(
+            value(&batch, "recent_watched", 0),
+            ScalarValue::Float64(Some(100.0))
+        );
+        assert!(!batch
+            .column_by_name("recent_watched")
+            .expect("column")
+            .is_null(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_keep_temporal_type() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Min, DescribeMethod::Max];
+        let query = "SELECT to_timestamp(created_at) AS created_at FROM users";
+        let batch = describe(&ctx, query, methods).await?;
+        let expected = ctx
+            .sql(&format!("SELECT min(created_at) FROM ({})", query))
+            .await?
+            .collect()
+            .await?;
+        let field = batch.schema().field_with_name("created_at")?.clone();
+        assert!(field.data_type().is_temporal());
+        assert_eq!(
+            value(&batch, "created_at", 0),
+            ScalarValue::try_from_array(expected[0].column(0), 0)?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_take_mode_on_values() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Mode, DescribeMethod::ModeFreq];
+        let batch = describe(&ctx, "SELECT position, kit_number FROM juventus", methods).await?;
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field_with_name("position")?.data_type(),
+            &DataType::Utf8
+        );
+        assert_eq!(
+            value(&batch, "position", 0),
+            ScalarValue::from("Central Midfield")
+        );
+        assert_eq!(value(&batch, "position", 1), ScalarValue::from("6"));
+        assert_eq!(
+            schema.field_with_name("kit_number")?.data_type(),
+            &DataType::Float64
+        );
+        assert_eq!(
+            value(&batch, "kit_number", 0),
+            ScalarValue::Float64(Some(1.0))
+        );
+        assert_eq!(
+            value(&batch, "kit_number", 1),
+            ScalarValue::Float64(Some(1.0))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_keep_counts_of_temporal_numeric() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let query = "SELECT to_timestamp(created_at) AS created_at FROM users";
+        let batch = describe(
+            &ctx,
+            query,
+            vec![DescribeMethod::Total, DescribeMethod::Skewness],
+        )
+        .await?;
+        assert_eq!(
+            batch.schema().field_with_name("created_at")?.data_type(),
+            &DataType::Float64
+        );
+        assert_eq!(
+            value(&batch, "created_at", 0),
+            ScalarValue::Float64(Some(100.0))
+        );
+
+        let batch = describe(
+            &ctx,
+            query,
+            vec![DescribeMethod::Total, DescribeMethod::Min],
+        )
+        .await?;
+        let dt = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        assert_eq!(
+            batch.schema().field_with_name("created_at")?.data_type(),
+            &dt
+        );
+        assert_eq!(
+            value(&batch, "created_at", 0),
+            ScalarValue::TimestampNanosecond(Some(100), None)
+        );
+        assert!(matches!(
+            value(&batch, "created_at", 1),
+            ScalarValue::TimestampNanosecond(Some(_), None)
+        ));
+
+        // the default statistics mix counts and values
+        let batch = describe(&ctx, query, DescribeMethod::defaults()).await?;
+        assert_eq!(
+            batch.schema().field_with_name("created_at")?.data_type(),
+            &dt
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_center_moments() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Skewness, DescribeMethod::Kurtosis];
+        let query = "SELECT kit_number, kit_number + 1e9 AS shifted FROM juventus";
+        let batch = describe(&ctx, query, methods).await?;
+        for column in ["kit_number", "shifted"] {
+            for (row, expected) in [(0, 2.0184591946726456), (1, 5.501765152445142)] {
+                match value(&batch, column, row) {
+                    ScalarValue::Float64(Some(v)) => {
+                        assert!((v - expected).abs() < 1e-6, "{}: {}", column, v)
+                    }
+                    v => panic!("unexpected moment: {}", v),
+                }
+            }
         }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_support_parquet() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = DescribeMethod::defaults();
+        let batch = describe(&ctx, "SELECT * FROM sample", methods).await?;
+        assert_eq!(
+            batch.num_columns(),
+            ctx.table("sample").await?.schema().fields().len() + 1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_group_by_columns() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let methods = vec![DescribeMethod::Total, DescribeMethod::ModeFreq];
+        let batch = describe_by(
+            &ctx,
+            "SELECT position, kit_number FROM juventus",
+            methods,
+            vec!["position".to_string()],
+        )
+        .await?;
+        let groups = ctx
+            .sql("SELECT DISTINCT position FROM juventus")
+            .await?
+            .count()
+            .await?;
+        assert_eq!(batch.num_rows(), groups * 2);
+        assert_eq!(batch.schema().field(0).name(), "position");
+        assert_eq!(batch.schema().field(1).name(), "stats");
+        let total = (0..batch.num_rows())
+            .step_by(2)
+            .map(|row| match value(&batch, "kit_number", row) {
+                ScalarValue::Float64(Some(v)) => v,
+                v => panic!("unexpected total: {}", v),
+            })
+            .sum::<f64>();
+        assert_eq!(total, 27.0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn describe_should_reject_unknown_group() -> anyhow::Result<()> {
+        let ctx = context().await?;
+        let df = ctx.sql("SELECT * FROM juventus").await?;
+        let ret = DataFrameDescriber::try_new(df, DescribeMethod::defaults(), vec!["team".into()]);
+        assert!(ret.is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,329 @@
+use std::{any::Any, sync::Arc};
+
+use ::duckdb::{AccessMode, Config, Connection};
+use arrow::{
+    array::{RecordBatch, RecordBatchOptions, StructArray},
+    datatypes::{Schema, SchemaRef},
+    ffi::{from_ffi, FFI_ArrowArray, FFI_ArrowSchema},
+};
+use async_trait::async_trait;
+use datafusion::{
+    datasource::{TableProvider, TableType},
+    error::{DataFusionError, Result},
+    execution::{context::SessionState, SendableRecordBatchStream, TaskContext},
+    logical_expr::{Operator, TableProviderFilterPushDown},
+    physical_plan::{
+        stream::RecordBatchStreamAdapter,
+        streaming::{PartitionStream, StreamingTableExec},
+        ExecutionPlan,
+    },
+    prelude::Expr,
+    sql::unparser::dialect::{Dialect, PostgreSqlDialect},
+};
+use tokio::sync::mpsc;
+
+use super::pushdown::{select_sql, Pushdown};
+
+/// A table of a DuckDB database file.
+///
+/// DuckDB hands its results over as Arrow batches, so columns keep the types
+/// it gives them. Projections, filters and LIMIT are pushed down as for
+/// SQLite.
+pub struct DuckDbTableProvider {
+    path: String,
+    table: String,
+    schema: SchemaRef,
+}
+
+struct DuckDbPartition {
+    path: String,
+    sql: String,
+    schema: SchemaRef,
+}
+
+impl DuckDbTableProvider {
+    pub fn try_new(path: &str, table: &str) -> anyhow::Result<Self> {
+        let table = quote_ident(table);
+        let conn = open(path)?;
+        let mut stmt = conn.prepare(&format!("SELECT * FROM {} LIMIT 0", table))?;
+        let schema = stmt.query_arrow([])?.get_schema();
+        let mut schema = ::duckdb::arrow::ffi::FFI_ArrowSchema::try_from(schema.as_ref())?;
+        // SAFETY: both versions of arrow lay out the C data interface structs
+        // the same way, and from_raw takes ownership of the one it is given
+        let schema = unsafe { FFI_ArrowSchema::from_raw(&mut schema as *mut _ as *mut _) };
+        Ok(Self {
+            path: path.to_string(),
+            table,
+            schema: Arc::new(Schema::try_from(&schema)?),
+        })
+    }
+
+    /// The tables and views of a database.
+    pub fn tables(path: &str) -> anyhow::Result<Vec<String>> {
+        let conn = open(path)?;
+        let mut stmt = conn.prepare(
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = 'main' ORDER BY table_name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(tables)
+    }
+}
+
+impl Pushdown for DuckDbTableProvider {
+    fn dialect(&self) -> &dyn Dialect {
+        &PostgreSqlDialect {}
+    }
+
+    fn is_pushable_column(&self, name: &str) -> bool {
+        self.schema.column_with_name(name).is_some()
+    }
+
+    /// `/` always divides as floats in DuckDB.
+    fn is_pushable_operator(&self, op: Operator, _left: &Expr, _right: &Expr) -> bool {
+        op != Operator::Divide
+    }
+}
+
+#[async_trait]
+impl TableProvider for DuckDbTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema.clone(),
+        };
+
+        let names = schema
+            .fields()
+            .iter()
+            .map(|f| quote_ident(f.name()))
+            .collect::<Vec<_>>();
+        let filters = filters
+            .iter()
+            .filter_map(|f| self.filter_to_sql(f))
+            .collect::<Vec<_>>();
+        let partition = DuckDbPartition {
+            path: self.path.clone(),
+            sql: select_sql(&names, &self.table, &filters, limit),
+            schema: schema.clone(),
+        };
+        Ok(Arc::new(StreamingTableExec::try_new(
+            schema,
+            vec![Arc::new(partition)],
+            None,
+            vec![],
+            false,
+            limit,
+        )?))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        Ok(filters.iter().map(|f| self.filter_pushdown(f)).collect())
+    }
+}
+
+impl PartitionStream for DuckDbPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let path = self.path.clone();
+        let sql = self.sql.clone();
+        let schema = self.schema.clone();
+
+        let (tx, rx) = mpsc::channel(2);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = query(&path, &sql, schema, &tx) {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+        let batches = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+        Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), batches))
+    }
+}
+
+fn open(path: &str) -> ::duckdb::Result<Connection> {
+    Connection::open_with_flags(path, Config::default().access_mode(AccessMode::ReadOnly)?)
+}
+
+/// Runs a scan, sending its batches until done or the receiver is dropped.
+fn query(
+    path: &str,
+    sql: &str,
+    schema: SchemaRef,
+    tx: &mpsc::Sender<Result<RecordBatch>>,
+) -> Result<()> {
+    let conn = open(path).map_err(ext)?;
+    let mut stmt = conn.prepare(sql).map_err(ext)?;
+    for batch in stmt.query_arrow([]).map_err(ext)? {
+        // without columns, only the rows of the constant selected are counted
+        let batch = if schema.fields().is_empty() {
+            let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+            RecordBatch::try_new_with_options(schema.clone(), vec![], &options)?
+        } else {
+            to_arrow(batch)?
+        };
+        if tx.blocking_send(Ok(batch)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// DuckDB builds on another major version of arrow, so its batches are
+/// moved over the C data interface.
+fn to_arrow(batch: ::duckdb::arrow::array::RecordBatch) -> Result<RecordBatch> {
+    use ::duckdb::arrow::{array::Array, ffi};
+
+    let data = ::duckdb::arrow::array::StructArray::from(batch).into_data();
+    let (mut array, mut schema) = ffi::to_ffi(&data).map_err(ext)?;
+    // SAFETY: both versions of arrow lay out the C data interface structs the
+    // same way, and from_raw takes ownership of the one it is given
+    let data = unsafe {
+        let array = FFI_ArrowArray::from_raw(&mut array as *mut _ as *mut _);
+        let schema = FFI_ArrowSchema::from_raw(&mut schema as *mut _ as *mut _);
+        from_ffi(array, &schema)?
+    };
+    Ok(StructArray::from(data).into())
+}
+
+fn quote_ident(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn ext<E: std::error::Error + Send + Sync + 'static>(e: E) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::AsArray,
+        datatypes::{DataType, Int32Type},
+    };
+    use datafusion::prelude::{col, lit, SessionContext};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn database() -> anyhow::Result<(TempDir, String)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.duckdb");
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER, name VARCHAR, score DOUBLE);
+             INSERT INTO users VALUES (1, 'alice', 1.5), (2, 'bob', NULL), (3, 'carol', 3.5);
+             CREATE VIEW scored AS SELECT * FROM users WHERE score IS NOT NULL;",
+        )?;
+        let path = path.to_str().expect("utf-8 path").to_string();
+        Ok((dir, path))
+    }
+
+    async fn query_ids(path: &str, sql: &str) -> anyhow::Result<Vec<i32>> {
+        let ctx = SessionContext::new();
+        ctx.register_table(
+            "users",
+            Arc::new(DuckDbTableProvider::try_new(path, "users")?),
+        )?;
+        let batches = ctx.sql(sql).await?.collect().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int32Type>().iter().flatten())
+            .collect())
+    }
+
+    #[test]
+    fn try_new_should_read_the_schema() -> anyhow::Result<()> {
+        let (_dir, path) = database()?;
+        let provider = DuckDbTableProvider::try_new(&path, "users")?;
+        let types = provider
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![DataType::Int32, DataType::Utf8, DataType::Float64]
+        );
+        assert!(DuckDbTableProvider::try_new(&path, "nope").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn tables_should_list_tables_and_views() -> anyhow::Result<()> {
+        let (_dir, path) = database()?;
+        assert_eq!(DuckDbTableProvider::tables(&path)?, vec!["scored", "users"]);
+        Ok(())
+    }
+
+    #[test]
+    fn divisions_should_be_left_to_datafusion() -> anyhow::Result<()> {
+        let (_dir, path) = database()?;
+        let provider = DuckDbTableProvider::try_new(&path, "users")?;
+        let pushdown = |e: Expr| provider.supports_filters_pushdown(&[&e]).unwrap()[0].clone();
+        assert_eq!(
+            pushdown(col("score").is_null()),
+            TableProviderFilterPushDown::Exact
+        );
+        assert_eq!(
+            pushdown((col("id") / lit(2)).eq(lit(1))),
+            TableProviderFilterPushDown::Unsupported
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_should_push_down_filters_and_limit() -> anyhow::Result<()> {
+        let (_dir, path) = database()?;
+        assert_eq!(
+            query_ids(
+                &path,
+                "SELECT id FROM users WHERE score IS NOT NULL AND name <> 'carol'"
+            )
+            .await?,
+            vec![1]
+        );
+        // integers divide as in DataFusion: 3 / 2 = 1
+        assert_eq!(
+            query_ids(&path, "SELECT id FROM users WHERE id / 2 = 1 ORDER BY id").await?,
+            vec![2, 3]
+        );
+        assert_eq!(
+            query_ids(&path, "SELECT id FROM users LIMIT 2")
+                .await?
+                .len(),
+            2
+        );
+        assert_eq!(
+            query_ids(&path, "SELECT count(*)::int FROM users").await?,
+            vec![3]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use arrow::{
+    array::RecordBatch,
+    compute::concat_batches,
+    csv,
+    datatypes::SchemaRef,
+    ipc,
+    json::{ArrayWriter, LineDelimitedWriter},
+    util::{
+        display::{ArrayFormatter, FormatOptions},
+        pretty::pretty_format_batches,
+    },
+};
+use parquet::arrow::ArrowWriter;
+
+use crate::{
+    backend::display,
+    cli::{OutputFile, OutputFormat, OutputWriter},
+    DisplayFormat, DisplayOpts, ReplOutput,
+};
+
+/// Writes `batches` to `output`, returning a message with the rows written.
+pub fn write_batches(
+    batches: &[RecordBatch],
+    schema: SchemaRef,
+    output: &OutputFile,
+) -> anyhow::Result<String> {
+    let mut writer = BatchWriter::try_new(schema, output)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    let rows = writer.finish()?;
+    Ok(format!("Wrote {} rows to {}", rows, output.path))
+}
+
+/// Writes batches to an output file as they come, so that a result needn't
+/// fit in memory to be exported.
+pub struct BatchWriter {
+    writer: FormatWriter,
+    rows: usize,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum FormatWriter {
+    Parquet(ArrowWriter<OutputWriter>),
+    Csv(csv::Writer<OutputWriter>),
+    NdJson(LineDelimitedWriter<OutputWriter>),
+    Arrow(ipc::writer::FileWriter<OutputWriter>),
+}
+
+impl BatchWriter {
+    pub fn try_new(schema: SchemaRef, output: &OutputFile) -> anyhow::Result<Self> {
+        let out = output.create()?;
+        let writer = match output.format {
+            OutputFormat::Parquet => {
+                FormatWriter::Parquet(ArrowWriter::try_new(out, schema, None)?)
+            }
+            OutputFormat::Csv => FormatWriter::Csv(csv::Writer::new(out)),
+            OutputFormat::NdJson => FormatWriter::NdJson(LineDelimitedWriter::new(out)),
+            OutputFormat::Arrow => {
+                FormatWriter::Arrow(ipc::writer::FileWriter::try_new(out, &schema)?)
+            }
+        };
+        Ok(Self { writer, rows: 0 })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        match &mut self.writer {
+            FormatWriter::Parquet(writer) => writer.write(batch)?,
+            FormatWriter::Csv(writer) => writer.write(batch)?,
+            FormatWriter::NdJson(writer) => writer.write(batch)?,
+            FormatWriter::Arrow(writer) => writer.write(batch)?,
+        }
+        self.rows += batch.num_rows();
+        Ok(())
+    }
+
+    /// Writes the footer of the format, if any, and of the compression.
+    /// Returns the rows written.
+    pub fn finish(self) -> anyhow::Result<usize> {
+        let out = match self.writer {
+            FormatWriter::Parquet(writer) => writer.into_inner()?,
+            FormatWriter::Csv(writer) => writer.into_inner(),
+            FormatWriter::NdJson(mut writer) => {
+                writer.finish()?;
+                writer.into_inner()
+            }
+            FormatWriter::Arrow(writer) => writer.into_inner()?,
+        };
+        out.finish()?;
+        Ok(self.rows)
+    }
+}
+
+/// Splits the rows of `batches`, out of `total` rows in the result, into
+/// pages formatted for the REPL.
+pub fn page_batches(
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+    total: usize,
+    opts: &DisplayOpts,
+) -> anyhow::Result<ReplOutput> {
+    let batch = concat_batches(&schema, batches)?;
+    let pages = opts
+        .pages(batch.num_rows())
+        .into_iter()
+        .map(|(offset, len)| {
+            format_batches(&[batch.slice(offset, len)], schema.clone(), opts.format)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(ReplOutput::paged(pages, total, batch.num_rows()))
+}
+
+/// Formats `batches` as text for the REPL.
+pub fn format_batches(
+    batches: &[RecordBatch],
+    schema: SchemaRef,
+    format: DisplayFormat,
+) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    match format {
+        DisplayFormat::Table => return Ok(pretty_format_batches(batches)?.to_string()),
+        DisplayFormat::Csv | DisplayFormat::Tsv => {
+            let delimiter = if format == DisplayFormat::Csv {
+                b','
+            } else {
+                b'\t'
+            };
+            let mut writer = csv::WriterBuilder::new()
+                .with_delimiter(delimiter)
+                .build(&mut buf);
+            for batch in batches {
+                writer.write(batch)?;
+            }
+        }
+        DisplayFormat::Json => {
+            let mut writer = ArrayWriter::new(&mut buf);
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+        }
+        DisplayFormat::NdJson => {
+            let mut writer = LineDelimitedWriter::new(&mut buf);
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.finish()?;
+        }
+        DisplayFormat::Markdown | DisplayFormat::Vertical => {
+            let headers = schema
+                .fields()
+                .iter()
+                .map(|f| f.name().to_string())
+                .collect::<Vec<_>>();
+            let rows = cells(batches)?;
+            return Ok(match format {
+                DisplayFormat::Markdown => display::markdown(&headers, &rows),
+                _ => display::vertical(&headers, &rows),
+            });
+        }
+    }
+    Ok(String::from_utf8(buf)?)
+}
+
+fn cells(batches: &[RecordBatch]) -> anyhow::Result<Vec<Vec<String>>> {
+    let options = FormatOptions::default();
+    let mut rows = Vec::new();
+    for batch in batches {
+        let formatters = batch
+            .columns()
+            .iter()
+            .map(|c| ArrayFormatter::try_new(c.as_ref(), &options))
+            .collect::<Result<Vec<_>, _>>()?;
+        for row in 0..batch.num_rows() {
+            rows.push(
+                formatters
+                    .iter()
+                    .map(|f| f.value(row).to_string())
+                    .collect(),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Int32Array, StringArray},
+        datatypes::{DataType, Field, Schema},
+    };
+
+    use super::*;
+
+    fn batch(rows: i32) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let names = (0..rows).map(|i| (i % 2 == 0).then(|| format!("n{}", i)));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..rows)),
+                Arc::new(StringArray::from_iter(names)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn format_batches_should_write_text_layouts() -> anyhow::Result<()> {
+        let batches = [batch(2)];
+        let schema = batches[0].schema();
+        assert_eq!(
+            format_batches(&batches, schema.clone(), DisplayFormat::Markdown)?,
+            "| id | name |\n| --- | --- |\n| 0 | n0 |\n| 1 |  |\n"
+        );
+        assert_eq!(
+            format_batches(&batches, schema.clone(), DisplayFormat::Vertical)?,
+            "-[ RECORD 1 ]------\nid   | 0\nname | n0\n\
+             -[ RECORD 2 ]------\nid   | 1\nname | \n"
+        );
+        assert_eq!(
+            format_batches(&batches, schema, DisplayFormat::Csv)?,
+            "id,name\n0,n0\n1,\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn page_batches_should_split_text_layouts() -> anyhow::Result<()> {
+        let batches = [batch(5)];
+        let opts = DisplayOpts {
+            format: DisplayFormat::Markdown,
+            max_rows: 0,
+            page_size: 2,
+        };
+        let output = page_batches(batches[0].schema(), &batches, 5, &opts)?;
+        let pages = output.pages;
+        assert_eq!(pages.len(), 3);
+        assert!(pages[2].ends_with("| 4 | n4 |\n"));
+        assert_eq!(pages[2].lines().count(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::anyhow;
+use arrow::{
+    array::{Array, AsArray, RecordBatch},
+    datatypes::{DataType, Field, Float64Type, Int64Type},
+};
+use datafusion::{
+    functions_aggregate::{count::count, expr_fn::approx_percentile_cont},
+    prelude::{cast, col, ident, lit, max, min, when, DataFrame, Expr},
+    scalar::ScalarValue,
+};
+
+use crate::backend::{hist, BinMethod, Histogram};
+
+use super::describe::transform;
+
+/// Value counts of `column`: top values for strings and booleans, bins over
+/// the describe mapping of the column for everything else.
+pub async fn histogram(
+    df: DataFrame,
+    column: &str,
+    bins: usize,
+    method: BinMethod,
+) -> anyhow::Result<Histogram> {
+    let field = df
+        .schema()
+        .field_with_unqualified_name(column)
+        .map_err(|_| anyhow!("Column not found: {}", column))?
+        .clone();
+    match field.data_type() {
+        DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Utf8View
+        | DataType::Boolean
+        | DataType::Dictionary(_, _) => top_values(df, column, bins).await,
+        _ => binned(df, &field, bins, method).await,
+    }
+}
+
+async fn top_values(df: DataFrame, column: &str, k: usize) -> anyhow::Result<Histogram> {
+    let total = df.clone().count().await? as u64;
+    let batches = df
+        .aggregate(
+            vec![ident(column).alias("value")],
+            vec![count(lit(1)).alias("freq")],
+        )?
+        .sort(vec![
+            col("freq").sort(false, false),
+            col("value").sort(true, false),
+        ])?
+        .limit(0, Some(k))?
+        .collect()
+        .await?;
+    let mut bars = Vec::new();
+    for batch in &batches {
+        let freqs = batch.column(1).as_primitive::<Int64Type>();
+        for row in 0..batch.num_rows() {
+            let value = ScalarValue::try_from_array(batch.column(0), row)?;
+            let label = match value.is_null() {
+                true => "NULL".to_string(),
+                false => value.to_string(),
+            };
+            bars.push((label, freqs.value(row) as u64));
+        }
+    }
+    Ok(Histogram::top_values(column, bars, total))
+}
+
+async fn binned(
+    df: DataFrame,
+    field: &Field,
+    bins: usize,
+    method: BinMethod,
+) -> anyhow::Result<Histogram> {
+    let x = cast(transform(field), DataType::Float64);
+    let mut aggregates = vec![
+        count(lit(1)).alias("rows"),
+        count(x.clone()).alias("values"),
+    ];
+    match method {
+        BinMethod::EqualWidth => {
+            aggregates.push(min(x.clone()).alias("e0"));
+            aggregates.push(max(x.clone()).alias("e1"));
+        }
+        BinMethod::Quantile => {
+            let quantiles = hist::quantile_fractions(bins).into_iter().enumerate();
+            aggregates.extend(
+                quantiles.map(|(i, q)| {
+                    approx_percentile_cont(x.clone(), lit(q)).alias(format!("e{}", i))
+                }),
+            );
+        }
+    }
+    let batches = df.clone().aggregate(vec![], aggregates)?.collect().await?;
+    let batch = batches
+        .iter()
+        .find(|b| b.num_rows() > 0)
+        .ok_or_else(|| anyhow!("No statistics returned for {}", field.name()))?;
+    let rows = int_value(batch, 0);
+    let nulls = rows - int_value(batch, 1);
+    let edges = batch.columns()[2..]
+        .iter()
+        .map(|c| c.as_primitive::<Float64Type>())
+        .filter(|c| c.is_valid(0))
+        .map(|c| c.value(0))
+        .collect::<Vec<_>>();
+    let edges = match (method, edges.as_slice()) {
+        (_, []) => vec![],
+        (BinMethod::EqualWidth, [min, max]) => hist::equal_width_edges(*min, *max, bins),
+        _ => hist::dedup_edges(edges),
+    };
+
+    let mut counts = Vec::new();
+    if !edges.is_empty() {
+        let batches = df
+            .filter(x.clone().is_not_null())?
+            .aggregate(
+                vec![bin(x, &edges, method)?.alias("bin")],
+                vec![count(lit(1)).alias("freq")],
+            )?
+            .collect()
+            .await?;
+        for batch in &batches {
+            let bins = batch.column(0).as_primitive::<Int64Type>();
+            let freqs = batch.column(1).as_primitive::<Int64Type>();
+            for row in 0..batch.num_rows() {
+                counts.push((bins.value(row) as usize, freqs.value(row) as u64));
+            }
+        }
+    }
+    let dt = field.data_type().clone();
+    let label = |v: f64| edge_label(v, &dt);
+    Ok(Histogram::binned(
+        field.name(),
+        &edges,
+        counts,
+        nulls,
+        label,
+    ))
+}
+
+/// Index of the bin `x` falls in; the last bin includes its upper edge.
+/// Equal width bins are computed from the width, the others looked up in a
+/// flat `CASE`, as nesting one per edge overflows the stack for many bins.
+fn bin(x: Expr, edges: &[f64], method: BinMethod) -> anyhow::Result<Expr> {
+    let n = edges.len() - 1;
+    let last = lit(n as i64 - 1);
+    match method {
+        BinMethod::EqualWidth => {
+            // at least 1 past the first bin, where truncating floors
+            let width = edges[1] - edges[0];
+            let i = cast((x.clone() - lit(edges[0])) / lit(width), DataType::Int64);
+            Ok(when(x.clone().gt_eq(lit(edges[n - 1])), last)
+                .when(x.lt(lit(edges[1])), lit(0_i64))
+                .otherwise(i)?)
+        }
+        BinMethod::Quantile => {
+            let mut case = when(x.clone().lt(lit(edges[1])), lit(0_i64));
+            for (i, edge) in edges.iter().enumerate().take(n).skip(2) {
+                case = case.when(x.clone().lt(lit(*edge)), lit(i as i64 - 1));
+            }
+            Ok(case.otherwise(last)?)
+        }
+    }
+}
+
+fn int_value(batch: &RecordBatch, i: usize) -> u64 {
+    batch.column(i).as_primitive::<Int64Type>().value(0) as u64
+}
+
+/// Temporal edges are shown in the column's own type.
+fn edge_label(v: f64, dt: &DataType) -> String {
+    if dt.is_temporal() {
+        if let Ok(value) = ScalarValue::Int64(Some(v as i64)).cast_to(dt) {
+            return value.to_string();
+        }
+    }
+    hist::format_edge(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::prelude::{CsvReadOptions, SessionContext};
+
+    use super::*;
+
+    async fn kit_numbers() -> anyhow::Result<DataFrame> {
+        let ctx = SessionContext::new();
+        ctx.register_csv("juventus", "assets/juventus.csv", CsvReadOptions::default())
+            .await?;
+        Ok(ctx.table("juventus").await?)
+    }
+
+    /// The sum of the counts ending the bars.
+    fn total(hist: &Histogram) -> u64 {
+        hist.to_string()
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.rsplit(' ').next()?.parse::<u64>().ok())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn binned_should_count_every_value() -> anyhow::Result<()> {
+        for method in [BinMethod::EqualWidth, BinMethod::Quantile] {
+            let hist = histogram(kit_numbers().await?, "kit_number", 4, method).await?;
+            assert_eq!(total(&hist), 27, "{:?}", method);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn binned_should_support_many_bins() -> anyhow::Result<()> {
+        for method in [BinMethod::EqualWidth, BinMethod::Quantile] {
+            let hist = histogram(kit_numbers().await?, "kit_number", 5_000, method).await?;
+            assert_eq!(total(&hist), 27, "{:?}", method);
+        }
+        Ok(())
+    }
+}
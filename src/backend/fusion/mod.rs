@@ -1,3 +1,11 @@
 pub mod data_fusion;
 mod describe;
-mod df_describe;
+mod duckdb;
+mod export;
+mod hist;
+mod mysql;
+mod postgres;
+mod pushdown;
+mod remote;
+mod spreadsheet;
+mod sqlite;
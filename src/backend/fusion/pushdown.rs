@@ -0,0 +1,279 @@
+use datafusion::{
+    common::ScalarValue,
+    logical_expr::{
+        expr_rewriter::unnormalize_col, BinaryExpr, Operator, TableProviderFilterPushDown,
+    },
+    prelude::Expr,
+    sql::unparser::{dialect::Dialect, Unparser},
+};
+
+/// Filters a database table provider can hand over to the database, as SQL
+/// of its dialect. Anything else is left to DataFusion.
+pub trait Pushdown {
+    fn dialect(&self) -> &dyn Dialect;
+
+    /// Whether the database compares the column the way DataFusion would.
+    fn is_pushable_column(&self, name: &str) -> bool;
+
+    fn is_pushable_literal(&self, _value: &ScalarValue) -> bool {
+        true
+    }
+
+    /// Whether the database evaluates `left op right` as DataFusion does,
+    /// for operands that are pushable themselves.
+    fn is_pushable_operator(&self, _op: Operator, _left: &Expr, _right: &Expr) -> bool {
+        true
+    }
+
+    fn supports_cast(&self) -> bool {
+        true
+    }
+
+    /// Whether `LIKE` is case sensitive in the database, as in DataFusion.
+    fn supports_like(&self) -> bool {
+        true
+    }
+
+    /// Whether the database reads nulls where DataFusion does.
+    fn supports_null_test(&self) -> bool {
+        true
+    }
+
+    /// Whether pushed filters select exactly the rows DataFusion would. If
+    /// not, they must select at least those rows, and DataFusion filters the
+    /// rows again.
+    fn is_exact(&self) -> bool {
+        true
+    }
+
+    fn filter_pushdown(&self, expr: &Expr) -> TableProviderFilterPushDown {
+        match self.filter_to_sql(expr) {
+            Some(_) if self.is_exact() => TableProviderFilterPushDown::Exact,
+            Some(_) => TableProviderFilterPushDown::Inexact,
+            None => TableProviderFilterPushDown::Unsupported,
+        }
+    }
+
+    fn filter_to_sql(&self, expr: &Expr) -> Option<String> {
+        if !self.is_pushable(expr) {
+            return None;
+        }
+        Unparser::new(self.dialect())
+            .expr_to_sql(&unnormalize_col(expr.clone()))
+            .ok()
+            .map(|sql| sql.to_string())
+    }
+
+    fn is_pushable(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Column(c) => self.is_pushable_column(&c.name),
+            Expr::Literal(v) => self.is_pushable_literal(v),
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                matches!(
+                    op,
+                    Operator::Eq
+                        | Operator::NotEq
+                        | Operator::Lt
+                        | Operator::LtEq
+                        | Operator::Gt
+                        | Operator::GtEq
+                        | Operator::And
+                        | Operator::Or
+                        | Operator::Plus
+                        | Operator::Minus
+                        | Operator::Multiply
+                        | Operator::Divide
+                ) && self.is_pushable(left)
+                    && self.is_pushable(right)
+                    && self.is_pushable_operator(*op, left, right)
+            }
+            Expr::IsNull(e) | Expr::IsNotNull(e) => {
+                self.supports_null_test() && self.is_pushable(e)
+            }
+            Expr::Not(e) | Expr::Negative(e) => self.is_pushable(e),
+            Expr::Cast(c) => self.supports_cast() && self.is_pushable(&c.expr),
+            Expr::Between(b) => {
+                self.is_pushable(&b.expr)
+                    && self.is_pushable(&b.low)
+                    && self.is_pushable(&b.high)
+                    && self.is_pushable_operator(Operator::GtEq, &b.expr, &b.low)
+                    && self.is_pushable_operator(Operator::LtEq, &b.expr, &b.high)
+            }
+            Expr::InList(l) => {
+                self.is_pushable(&l.expr)
+                    && l.list.iter().all(|e| {
+                        self.is_pushable(e) && self.is_pushable_operator(Operator::Eq, &l.expr, e)
+                    })
+            }
+            Expr::Like(l) => {
+                self.supports_like()
+                    && l.escape_char.is_none()
+                    && self.is_pushable(&l.expr)
+                    && self.is_pushable(&l.pattern)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The `SELECT` a database runs for a scan. Without columns, as for
+/// `count(*)`, a constant is selected to count the rows.
+pub fn select_sql(
+    columns: &[String],
+    table: &str,
+    filters: &[String],
+    limit: Option<usize>,
+) -> String {
+    let columns = match columns {
+        [] => "1".to_string(),
+        columns => columns.join(", "),
+    };
+    let mut sql = format!("SELECT {} FROM {}", columns, table);
+    if !filters.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&filters.join(" AND "));
+    }
+    if let Some(limit) = limit {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::{
+        prelude::{col, lit},
+        sql::unparser::dialect::{MySqlDialect, PostgreSqlDialect, SqliteDialect},
+    };
+
+    use super::*;
+
+    /// Pushes down anything on columns `a` and `b`.
+    struct Table<D>(D);
+
+    impl<D: Dialect> Pushdown for Table<D> {
+        fn dialect(&self) -> &dyn Dialect {
+            &self.0
+        }
+
+        fn is_pushable_column(&self, name: &str) -> bool {
+            name == "a" || name == "b"
+        }
+    }
+
+    #[test]
+    fn select_sql_should_add_filters_and_limit() {
+        let columns = vec!["\"a\"".to_string(), "\"b\"".to_string()];
+        let filters = vec!["(a > 1)".to_string(), "(b = 'x')".to_string()];
+        assert_eq!(
+            select_sql(&columns, "\"t\"", &filters, Some(10)),
+            "SELECT \"a\", \"b\" FROM \"t\" WHERE (a > 1) AND (b = 'x') LIMIT 10"
+        );
+        assert_eq!(
+            select_sql(&columns, "\"t\"", &[], None),
+            "SELECT \"a\", \"b\" FROM \"t\""
+        );
+    }
+
+    #[test]
+    fn select_sql_should_select_a_constant_without_columns() {
+        assert_eq!(
+            select_sql(&[], "`t`", &[], Some(0)),
+            "SELECT 1 FROM `t` LIMIT 0"
+        );
+    }
+
+    #[test]
+    fn filter_to_sql_should_quote_per_dialect() {
+        let filter = col("a").gt(lit(1)).and(col("b").eq(lit("it's")));
+        assert_eq!(
+            Table(PostgreSqlDialect {})
+                .filter_to_sql(&filter)
+                .as_deref(),
+            Some("((\"a\" > 1) AND (\"b\" = 'it''s'))")
+        );
+        assert_eq!(
+            Table(MySqlDialect {}).filter_to_sql(&filter).as_deref(),
+            Some("((`a` > 1) AND (`b` = 'it''s'))")
+        );
+        assert_eq!(
+            Table(SqliteDialect {}).filter_to_sql(&filter).as_deref(),
+            Some("((`a` > 1) AND (`b` = 'it''s'))")
+        );
+    }
+
+    #[test]
+    fn filter_to_sql_should_leave_unknown_columns_to_datafusion() {
+        let table = Table(PostgreSqlDialect {});
+        assert!(table.filter_to_sql(&col("c").eq(lit(1))).is_none());
+        assert!(table
+            .filter_to_sql(&col("a").eq(lit(1)).or(col("c").eq(lit(1))))
+            .is_none());
+        assert_eq!(
+            table.filter_pushdown(&col("c").is_null()),
+            TableProviderFilterPushDown::Unsupported
+        );
+    }
+
+    #[test]
+    fn filter_to_sql_should_support_null_tests_in_lists_and_ranges() {
+        let table = Table(PostgreSqlDialect {});
+        assert_eq!(
+            table.filter_to_sql(&col("a").is_null()).as_deref(),
+            Some("\"a\" IS NULL")
+        );
+        assert_eq!(
+            table
+                .filter_to_sql(&col("a").in_list(vec![lit(1), lit(2)], false))
+                .as_deref(),
+            Some("\"a\" IN (1, 2)")
+        );
+        assert_eq!(
+            table
+                .filter_to_sql(&col("a").between(lit(1), lit(2)))
+                .as_deref(),
+            Some("(\"a\" BETWEEN 1 AND 2)")
+        );
+        assert_eq!(
+            table.filter_pushdown(&col("a").is_null()),
+            TableProviderFilterPushDown::Exact
+        );
+    }
+
+    /// Like `Table`, but the database divides and tests nulls its own way.
+    struct Loose;
+
+    impl Pushdown for Loose {
+        fn dialect(&self) -> &dyn Dialect {
+            &SqliteDialect {}
+        }
+
+        fn is_pushable_column(&self, name: &str) -> bool {
+            name == "a"
+        }
+
+        fn is_pushable_operator(&self, op: Operator, _left: &Expr, _right: &Expr) -> bool {
+            op != Operator::Divide
+        }
+
+        fn supports_null_test(&self) -> bool {
+            false
+        }
+
+        fn is_exact(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn filter_pushdown_should_follow_the_opt_outs() {
+        let filter = (col("a") / lit(2)).eq(lit(2));
+        assert!(Loose.filter_to_sql(&filter).is_none());
+        assert!(Table(SqliteDialect {}).filter_to_sql(&filter).is_some());
+        assert!(Loose.filter_to_sql(&col("a").is_not_null()).is_none());
+        assert_eq!(
+            Loose.filter_pushdown(&(col("a") + lit(2)).eq(lit(4))),
+            TableProviderFilterPushDown::Inexact
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::{env, fs, sync::Arc};
+
+use anyhow::{anyhow, bail};
+use datafusion::prelude::SessionContext;
+use object_store::{aws::AmazonS3Builder, http::HttpBuilder, ClientOptions, ObjectStore};
+use url::Url;
+
+use crate::cli::RemoteOpts;
+
+/// Registers the store a URL points into with the session, so DataFusion
+/// can list and read its files.
+pub fn register_store(ctx: &SessionContext, url: &str, opts: &RemoteOpts) -> anyhow::Result<()> {
+    let url = Url::parse(url)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("No bucket or host in {}", url))?;
+    let base = match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+        None => format!("{}://{}", url.scheme(), host),
+    };
+    let store: Arc<dyn ObjectStore> = match url.scheme() {
+        "s3" | "s3a" => Arc::new(s3_builder(host, opts).build()?),
+        "http" | "https" => Arc::new(
+            HttpBuilder::new()
+                .with_url(&base)
+                .with_client_options(ClientOptions::new().with_allow_http(true))
+                .build()?,
+        ),
+        scheme => bail!("Unsupported URL scheme: {}", scheme),
+    };
+    ctx.register_object_store(&Url::parse(&base)?, store);
+    Ok(())
+}
+
+/// Credentials come from the `AWS_*` variables, or else from the profile
+/// `AWS_PROFILE` names in `~/.aws/credentials`.
+fn s3_builder(bucket: &str, opts: &RemoteOpts) -> AmazonS3Builder {
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+    if env::var_os("AWS_ACCESS_KEY_ID").is_none() {
+        if let Some(profile) = Profile::load() {
+            builder = builder
+                .with_access_key_id(profile.access_key_id)
+                .with_secret_access_key(profile.secret_access_key);
+            if let Some(token) = profile.session_token {
+                builder = builder.with_token(token);
+            }
+        }
+    }
+    if let Some(region) = &opts.region {
+        builder = builder.with_region(region);
+    }
+    if let Some(endpoint) = &opts.endpoint {
+        builder = builder
+            .with_endpoint(endpoint)
+            .with_allow_http(endpoint.starts_with("http://"));
+    }
+    builder
+}
+
+struct Profile {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Profile {
+    fn load() -> Option<Self> {
+        let name = env::var("AWS_PROFILE").unwrap_or_else(|_| "default".to_string());
+        let path = dirs::home_dir()?.join(".aws").join("credentials");
+        Self::parse(&fs::read_to_string(path).ok()?, &name)
+    }
+
+    /// The profile `name` of a credentials file, if it has the keys.
+    fn parse(content: &str, name: &str) -> Option<Self> {
+        let mut section = None;
+        let (mut key_id, mut secret, mut token) = (None, None, None);
+        for line in content.lines().map(str::trim) {
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(header.trim().to_string());
+                continue;
+            }
+            if section.as_deref() != Some(name) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "aws_access_key_id" => key_id = value,
+                "aws_secret_access_key" => secret = value,
+                "aws_session_token" => token = value,
+                _ => {}
+            }
+        }
+        Some(Self {
+            access_key_id: key_id?,
+            secret_access_key: secret?,
+            session_token: token,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use datafusion::{execution::object_store::ObjectStoreUrl, prelude::CsvReadOptions};
+
+    use super::*;
+
+    const CREDENTIALS: &str = "
+[default]
+aws_access_key_id = AKIDEFAULT
+aws_secret_access_key = secret
+
+[dev]
+aws_access_key_id=AKIDEV
+aws_secret_access_key=dev secret
+aws_session_token = token
+region = eu-west-1
+";
+
+    #[test]
+    fn profile_should_read_its_own_section() {
+        let profile = Profile::parse(CREDENTIALS, "default").expect("default profile");
+        assert_eq!(profile.access_key_id, "AKIDEFAULT");
+        assert_eq!(profile.secret_access_key, "secret");
+        assert_eq!(profile.session_token, None);
+
+        let profile = Profile::parse(CREDENTIALS, "dev").expect("dev profile");
+        assert_eq!(profile.access_key_id, "AKIDEV");
+        assert_eq!(profile.secret_access_key, "dev secret");
+        assert_eq!(profile.session_token.as_deref(), Some("token"));
+
+        assert!(Profile::parse(CREDENTIALS, "prod").is_none());
+        assert!(Profile::parse("[broken]\naws_access_key_id = x\n", "broken").is_none());
+    }
+
+    #[test]
+    fn register_store_should_register_per_bucket_or_host() -> anyhow::Result<()> {
+        let ctx = SessionContext::new();
+        let opts = RemoteOpts {
+            endpoint: Some("http://localhost:9000".to_string()),
+            region: Some("us-east-1".to_string()),
+        };
+        register_store(&ctx, "s3://bucket/data/*.parquet", &opts)?;
+        register_store(&ctx, "https://example.com:8443/data.csv", &opts)?;
+        let env = ctx.runtime_env();
+        assert!(env
+            .object_store(ObjectStoreUrl::parse("s3://bucket/")?)
+            .is_ok());
+        assert!(env
+            .object_store(ObjectStoreUrl::parse("https://example.com:8443/")?)
+            .is_ok());
+        assert!(env
+            .object_store(ObjectStoreUrl::parse("https://example.com/")?)
+            .is_err());
+
+        assert!(register_store(&ctx, "ftp://example.com/data.csv", &opts).is_err());
+        assert!(register_store(&ctx, "s3:///data.csv", &opts).is_err());
+        Ok(())
+    }
+
+    /// Serves `body` at every path, for as many requests as it gets.
+    fn serve(body: &'static str) -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nLast-Modified: Tue, 01 Oct 2024 00:00:00 GMT\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes());
+                if !request.starts_with(b"HEAD") {
+                    let _ = stream.write_all(body.as_bytes());
+                }
+            }
+        });
+        Ok(format!("http://{}", addr))
+    }
+
+    #[tokio::test]
+    async fn registered_http_store_should_be_read() -> anyhow::Result<()> {
+        let base = serve("id,name\n1,alice\n2,bob\n")?;
+        let url = format!("{}/users.csv", base);
+        let ctx = SessionContext::new();
+        register_store(&ctx, &url, &RemoteOpts::default())?;
+        ctx.register_csv("users", &url, CsvReadOptions::default())
+            .await?;
+        assert_eq!(ctx.table("users").await?.count().await?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+        TimestampMicrosecondArray,
+    },
+    datatypes::{Field, Schema},
+};
+use calamine::{open_workbook, Data, DataType as _, Range, Reader, Sheets};
+
+/// Reads a worksheet of an Excel or OpenDocument file, the only one if
+/// `sheet` is not given.
+///
+/// The first row is taken for the header when all its cells are text, unless
+/// `no_header`. A column gets the type shared by its cells, integers and
+/// floats making floats; mixed columns are read as text.
+pub fn read_sheet(
+    path: &str,
+    ext: &str,
+    sheet: Option<&str>,
+    no_header: bool,
+) -> anyhow::Result<RecordBatch> {
+    let mut workbook: Sheets<_> = match ext {
+        "xls" => Sheets::Xls(open_workbook(path)?),
+        "xlsb" => Sheets::Xlsb(open_workbook(path)?),
+        "ods" => Sheets::Ods(open_workbook(path)?),
+        _ => Sheets::Xlsx(open_workbook(path)?),
+    };
+    let names = workbook.sheet_names();
+    let name = match (sheet, names.as_slice()) {
+        (Some(sheet), names) if names.iter().any(|n| n == sheet) => sheet.to_string(),
+        (Some(sheet), names) => {
+            bail!(
+                "No sheet {} in {}, sheets are: {}",
+                sheet,
+                path,
+                names.join(", ")
+            )
+        }
+        (None, [name]) => name.clone(),
+        (None, names) => bail!(
+            "{} has several sheets, choose one with --sheet: {}",
+            path,
+            names.join(", ")
+        ),
+    };
+    let range = workbook.worksheet_range(&name)?;
+    to_batch(&range, no_header)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellKind {
+    Bool,
+    Int,
+    Float,
+    DateTime,
+    Text,
+}
+
+fn to_batch(range: &Range<Data>, no_header: bool) -> anyhow::Result<RecordBatch> {
+    let mut rows = range.rows().peekable();
+    let has_header = !no_header && rows.peek().is_some_and(|row| is_header(row));
+    let header = if has_header { rows.next() } else { None };
+    let rows = rows.collect::<Vec<_>>();
+    let width = range.width();
+    let names = column_names(header, width);
+
+    let mut fields = Vec::with_capacity(width);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(width);
+    for (i, name) in names.into_iter().enumerate() {
+        let cells = rows.iter().map(|row| row.get(i).unwrap_or(&Data::Empty));
+        let kind = cells
+            .clone()
+            .filter_map(cell_kind)
+            .reduce(merge)
+            .unwrap_or(CellKind::Text);
+        let column: ArrayRef = match kind {
+            CellKind::Bool => Arc::new(cells.map(|c| c.get_bool()).collect::<BooleanArray>()),
+            CellKind::Int => Arc::new(cells.map(|c| c.as_i64()).collect::<Int64Array>()),
+            CellKind::Float => Arc::new(cells.map(|c| c.as_f64()).collect::<Float64Array>()),
+            CellKind::DateTime => Arc::new(
+                cells
+                    .map(|c| c.as_datetime().map(|d| d.and_utc().timestamp_micros()))
+                    .collect::<TimestampMicrosecondArray>(),
+            ),
+            CellKind::Text => Arc::new(cells.map(text).collect::<StringArray>()),
+        };
+        fields.push(Field::new(name, column.data_type().clone(), true));
+        columns.push(column);
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+fn is_header(row: &[Data]) -> bool {
+    row.iter().any(|c| !c.is_empty())
+        && row
+            .iter()
+            .all(|c| matches!(c, Data::String(_) | Data::Empty))
+}
+
+/// Names from the header, `column_N` for missing ones; repeated names get a
+/// suffix as tables can't have two columns of the same name.
+fn column_names(header: Option<&[Data]>, width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for i in 0..width {
+        let name = match header.and_then(|h| h.get(i)) {
+            Some(Data::String(s)) if !s.trim().is_empty() => s.trim().to_string(),
+            _ => format!("column_{}", i + 1),
+        };
+        let mut unique = name.clone();
+        let mut n = 1;
+        while names.contains(&unique) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+        names.push(unique);
+    }
+    names
+}
+
+/// Empty and error cells are read as null.
+fn cell_kind(cell: &Data) -> Option<CellKind> {
+    match cell {
+        Data::Empty | Data::Error(_) => None,
+        Data::Bool(_) => Some(CellKind::Bool),
+        Data::Int(_) => Some(CellKind::Int),
+        // spreadsheets store every number as a float
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => Some(CellKind::Int),
+        Data::Float(_) => Some(CellKind::Float),
+        Data::DateTime(_) | Data::DateTimeIso(_) if cell.as_datetime().is_some() => {
+            Some(CellKind::DateTime)
+        }
+        _ => Some(CellKind::Text),
+    }
+}
+
+fn merge(a: CellKind, b: CellKind) -> CellKind {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (CellKind::Int, CellKind::Float) | (CellKind::Float, CellKind::Int) => CellKind::Float,
+        _ => CellKind::Text,
+    }
+}
+
+fn text(cell: &Data) -> Option<String> {
+    match cell {
+        Data::Empty | Data::Error(_) => None,
+        Data::String(s) => Some(s.clone()),
+        Data::DateTime(_) => cell.as_datetime().map(|d| d.to_string()),
+        _ => Some(cell.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Array, AsArray},
+        datatypes::{DataType, Float64Type, Int64Type, TimeUnit, TimestampMicrosecondType},
+    };
+    use calamine::CellErrorType;
+
+    use super::*;
+
+    const PLAYERS: &str = "assets/players.xlsx";
+
+    fn range(rows: Vec<Vec<Data>>) -> Range<Data> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default() as u32;
+        let mut range = Range::new((0, 0), (rows.len() as u32 - 1, width - 1));
+        for (i, row) in rows.into_iter().enumerate() {
+            for (j, cell) in row.into_iter().enumerate() {
+                range.set_value((i as u32, j as u32), cell);
+            }
+        }
+        range
+    }
+
+    fn s(s: &str) -> Data {
+        Data::String(s.to_string())
+    }
+
+    fn names(batch: &RecordBatch) -> Vec<String> {
+        let schema = batch.schema();
+        schema.fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    fn types(batch: &RecordBatch) -> Vec<DataType> {
+        let schema = batch.schema();
+        schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect()
+    }
+
+    #[test]
+    fn to_batch_should_take_a_text_row_for_header() -> anyhow::Result<()> {
+        let rows = vec![
+            vec![s("id"), Data::Empty, s("id"), s(" ")],
+            vec![Data::Int(1), s("a"), Data::Int(2), Data::Empty],
+        ];
+        let batch = to_batch(&range(rows.clone()), false)?;
+        assert_eq!(names(&batch), ["id", "column_2", "id_2", "column_4"]);
+        assert_eq!(batch.num_rows(), 1);
+
+        let batch = to_batch(&range(rows), true)?;
+        assert_eq!(names(&batch)[0], "column_1");
+        assert_eq!(batch.num_rows(), 2);
+
+        // a number in the first row makes it data
+        let rows = vec![
+            vec![s("id"), Data::Float(1.5)],
+            vec![s("a"), Data::Float(2.5)],
+        ];
+        let batch = to_batch(&range(rows), false)?;
+        assert_eq!(names(&batch), ["column_1", "column_2"]);
+        assert_eq!(batch.num_rows(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn to_batch_should_merge_cell_types() -> anyhow::Result<()> {
+        let rows = vec![
+            vec![s("whole"), s("float"), s("mixed"), s("flag"), s("empty")],
+            vec![
+                Data::Float(1.0),
+                Data::Int(1),
+                Data::Float(1.0),
+                Data::Bool(true),
+                Data::Empty,
+            ],
+            vec![
+                Data::Float(2.0),
+                Data::Float(1.5),
+                s("x"),
+                Data::Empty,
+                Data::Error(CellErrorType::NA),
+            ],
+        ];
+        let batch = to_batch(&range(rows), false)?;
+        assert_eq!(
+            types(&batch),
+            [
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+                DataType::Boolean,
+                DataType::Utf8
+            ]
+        );
+        let whole = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(whole.values(), &[1, 2]);
+        let float = batch.column(1).as_primitive::<Float64Type>();
+        assert_eq!(float.values(), &[1.0, 1.5]);
+        let mixed = batch.column(2).as_string::<i32>();
+        assert_eq!(mixed.iter().collect::<Vec<_>>(), [Some("1"), Some("x")]);
+        assert_eq!(batch.column(3).null_count(), 1);
+        assert_eq!(batch.column(4).null_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn read_sheet_should_read_the_chosen_sheet() -> anyhow::Result<()> {
+        let batch = read_sheet(PLAYERS, "xlsx", Some("players"), false)?;
+        assert_eq!(
+            names(&batch),
+            ["name", "number", "height", "senior", "joined", "note", "column_7", "name_2"]
+        );
+        assert_eq!(
+            types(&batch),
+            [
+                DataType::Utf8,
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Boolean,
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                DataType::Utf8,
+                DataType::Int64,
+                DataType::Utf8,
+            ]
+        );
+        assert_eq!(batch.num_rows(), 3);
+        let joined = batch.column(4).as_primitive::<TimestampMicrosecondType>();
+        // 2001-07-01
+        assert_eq!(joined.value(0), 993_945_600_000_000);
+        assert!(joined.is_null(2));
+        let note = batch.column(5).as_string::<i32>();
+        assert_eq!(
+            note.iter().collect::<Vec<_>>(),
+            [Some("keeper"), Some("42"), None]
+        );
+
+        let batch = read_sheet(PLAYERS, "xlsx", Some("notes"), false)?;
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(names(&batch), ["Players of the 2015-16 season"]);
+        Ok(())
+    }
+
+    #[test]
+    fn read_sheet_should_require_a_known_sheet() {
+        let err = read_sheet(PLAYERS, "xlsx", None, false).unwrap_err();
+        assert!(
+            err.to_string().contains("--sheet: players, notes"),
+            "{}",
+            err
+        );
+        let err = read_sheet(PLAYERS, "xlsx", Some("missing"), false).unwrap_err();
+        assert!(
+            err.to_string().contains("sheets are: players, notes"),
+            "{}",
+            err
+        );
+    }
+}
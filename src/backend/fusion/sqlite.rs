@@ -0,0 +1,619 @@
+use std::{any::Any, sync::Arc};
+
+use anyhow::bail;
+use arrow::{
+    array::{
+        ArrayRef, BinaryArray, BooleanArray, Date32Array, Float64Array, Int64Array, RecordBatch,
+        RecordBatchOptions, StringArray, TimestampMicrosecondArray,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use datafusion::{
+    common::ScalarValue,
+    datasource::{TableProvider, TableType},
+    error::{DataFusionError, Result},
+    execution::{context::SessionState, SendableRecordBatchStream, TaskContext},
+    logical_expr::{Operator, TableProviderFilterPushDown},
+    physical_plan::{
+        stream::RecordBatchStreamAdapter,
+        streaming::{PartitionStream, StreamingTableExec},
+        ExecutionPlan,
+    },
+    prelude::Expr,
+    sql::unparser::dialect::{Dialect, SqliteDialect},
+};
+use rusqlite::{types::Value, Connection, OpenFlags};
+use tokio::sync::mpsc;
+
+use super::pushdown::{select_sql, Pushdown};
+
+/// A table of a SQLite database file.
+///
+/// Columns get the Arrow type of their declared type's affinity.
+/// Projections, simple filters and LIMIT are pushed down into the `SELECT`
+/// run by SQLite, which streams its rows on a blocking thread. Values that
+/// don't convert to their column's type are read as null, where SQLite still
+/// compares them, so pushed filters are inexact and DataFusion applies them
+/// again.
+pub struct SqliteTableProvider {
+    path: String,
+    table: String,
+    columns: Vec<SqliteColumn>,
+    schema: SchemaRef,
+}
+
+#[derive(Debug, Clone)]
+struct SqliteColumn {
+    name: String,
+    kind: SqliteKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqliteKind {
+    Integer,
+    Real,
+    Text,
+    Blob,
+    Boolean,
+    Date,
+    Timestamp,
+    /// No declared type, values of any storage class read as text.
+    Any,
+}
+
+struct SqlitePartition {
+    path: String,
+    sql: String,
+    columns: Vec<SqliteColumn>,
+    schema: SchemaRef,
+}
+
+impl SqliteTableProvider {
+    pub fn try_new(path: &str, table: &str) -> anyhow::Result<Self> {
+        let conn = open(path)?;
+        let mut stmt = conn.prepare("SELECT name, type FROM pragma_table_info(?1)")?;
+        let columns = stmt
+            .query_map([table], |row| {
+                Ok(SqliteColumn::new(row.get(0)?, &row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            bail!("No table {} in {}", table, path);
+        }
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|c| Field::new(&c.name, c.kind.data_type(), true))
+                .collect::<Vec<_>>(),
+        ));
+
+        Ok(Self {
+            path: path.to_string(),
+            table: quote_ident(table),
+            columns,
+            schema,
+        })
+    }
+
+    /// The tables and views of a database.
+    pub fn tables(path: &str) -> anyhow::Result<Vec<String>> {
+        let conn = open(path)?;
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let tables = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(tables)
+    }
+}
+
+impl Pushdown for SqliteTableProvider {
+    fn dialect(&self) -> &dyn Dialect {
+        &SqliteDialect {}
+    }
+
+    /// Booleans, dates and timestamps are stored as text or numbers, they
+    /// don't compare like DataFusion's.
+    fn is_pushable_column(&self, name: &str) -> bool {
+        self.columns.iter().any(|col| {
+            col.name == name
+                && matches!(
+                    col.kind,
+                    SqliteKind::Integer | SqliteKind::Real | SqliteKind::Text
+                )
+        })
+    }
+
+    fn is_pushable_literal(&self, value: &ScalarValue) -> bool {
+        matches!(
+            value,
+            ScalarValue::Boolean(_)
+                | ScalarValue::Int8(_)
+                | ScalarValue::Int16(_)
+                | ScalarValue::Int32(_)
+                | ScalarValue::Int64(_)
+                | ScalarValue::UInt8(_)
+                | ScalarValue::UInt16(_)
+                | ScalarValue::UInt32(_)
+                | ScalarValue::Float32(_)
+                | ScalarValue::Float64(_)
+                | ScalarValue::Utf8(_)
+        )
+    }
+
+    /// SQLite casts by affinity, not to the types DataFusion names.
+    fn supports_cast(&self) -> bool {
+        false
+    }
+
+    fn supports_like(&self) -> bool {
+        false
+    }
+
+    /// Division by zero is null in SQLite, an error in DataFusion.
+    fn is_pushable_operator(&self, op: Operator, _left: &Expr, _right: &Expr) -> bool {
+        op != Operator::Divide
+    }
+
+    /// Values read as null may not be null in SQLite.
+    fn supports_null_test(&self) -> bool {
+        false
+    }
+
+    fn is_exact(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl TableProvider for SqliteTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let columns = match projection {
+            Some(projection) => projection
+                .iter()
+                .map(|i| self.columns[*i].clone())
+                .collect::<Vec<_>>(),
+            None => self.columns.clone(),
+        };
+        let schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema.clone(),
+        };
+
+        let names = columns
+            .iter()
+            .map(|c| quote_ident(&c.name))
+            .collect::<Vec<_>>();
+        let filters = filters
+            .iter()
+            .filter_map(|f| self.filter_to_sql(f))
+            .collect::<Vec<_>>();
+        let partition = SqlitePartition {
+            path: self.path.clone(),
+            sql: select_sql(&names, &self.table, &filters, limit),
+            columns,
+            schema: schema.clone(),
+        };
+        Ok(Arc::new(StreamingTableExec::try_new(
+            schema,
+            vec![Arc::new(partition)],
+            None,
+            vec![],
+            false,
+            limit,
+        )?))
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        Ok(filters.iter().map(|f| self.filter_pushdown(f)).collect())
+    }
+}
+
+impl PartitionStream for SqlitePartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let batch_size = ctx.session_config().batch_size();
+        let path = self.path.clone();
+        let sql = self.sql.clone();
+        let columns = self.columns.clone();
+        let schema = self.schema.clone();
+
+        let (tx, rx) = mpsc::channel(2);
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = query(&path, &sql, &columns, schema, batch_size, &tx) {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+        let batches = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+        Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), batches))
+    }
+}
+
+impl SqliteColumn {
+    /// The affinity rules of <https://www.sqlite.org/datatype3.html>, with
+    /// booleans, dates and timestamps told apart by their declared type.
+    fn new(name: String, declared: &str) -> Self {
+        let declared = declared.to_ascii_uppercase();
+        let has = |s: &str| declared.contains(s);
+        let kind = if has("INT") {
+            SqliteKind::Integer
+        } else if has("CHAR") || has("CLOB") || has("TEXT") {
+            SqliteKind::Text
+        } else if has("BLOB") {
+            SqliteKind::Blob
+        } else if declared.is_empty() {
+            // as for computed columns of views
+            SqliteKind::Any
+        } else if has("REAL") || has("FLOA") || has("DOUB") {
+            SqliteKind::Real
+        } else if has("BOOL") {
+            SqliteKind::Boolean
+        } else if has("TIMESTAMP") || has("DATETIME") {
+            SqliteKind::Timestamp
+        } else if has("DATE") {
+            SqliteKind::Date
+        } else {
+            SqliteKind::Real
+        };
+        Self { name, kind }
+    }
+}
+
+impl SqliteKind {
+    fn data_type(&self) -> DataType {
+        match self {
+            SqliteKind::Integer => DataType::Int64,
+            SqliteKind::Real => DataType::Float64,
+            SqliteKind::Text | SqliteKind::Any => DataType::Utf8,
+            SqliteKind::Blob => DataType::Binary,
+            SqliteKind::Boolean => DataType::Boolean,
+            SqliteKind::Date => DataType::Date32,
+            SqliteKind::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+        }
+    }
+}
+
+fn open(path: &str) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+/// Runs a scan, sending its rows in batches until done or the receiver is
+/// dropped.
+fn query(
+    path: &str,
+    sql: &str,
+    columns: &[SqliteColumn],
+    schema: SchemaRef,
+    batch_size: usize,
+    tx: &mpsc::Sender<Result<RecordBatch>>,
+) -> Result<()> {
+    let conn = open(path).map_err(ext)?;
+    let mut stmt = conn.prepare(sql).map_err(ext)?;
+    let mut rows = stmt.query([]).map_err(ext)?;
+    let mut chunk = Vec::with_capacity(batch_size);
+    while let Some(row) = rows.next().map_err(ext)? {
+        let values = (0..columns.len())
+            .map(|i| row.get::<_, Value>(i))
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(ext)?;
+        chunk.push(values);
+        if chunk.len() == batch_size {
+            let batch = rows_to_batch(schema.clone(), columns, &chunk)?;
+            chunk.clear();
+            if tx.blocking_send(Ok(batch)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        let _ = tx.blocking_send(rows_to_batch(schema, columns, &chunk));
+    }
+    Ok(())
+}
+
+fn quote_ident(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn ext<E: std::error::Error + Send + Sync + 'static>(e: E) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+/// SQLite values of any type may be stored in any column, those that don't
+/// convert to the column's type are read as null. Numeric columns already
+/// hold numeric looking text as numbers, other text is not a number.
+fn rows_to_batch(
+    schema: SchemaRef,
+    columns: &[SqliteColumn],
+    rows: &[Vec<Value>],
+) -> Result<RecordBatch> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch");
+    let arrays = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let values = rows.iter().map(|row| &row[i]);
+            let array: ArrayRef = match c.kind {
+                SqliteKind::Integer => Arc::new(values.map(as_i64).collect::<Int64Array>()),
+                SqliteKind::Real => Arc::new(values.map(as_f64).collect::<Float64Array>()),
+                // blobs compare above any text in SQLite
+                SqliteKind::Text => Arc::new(
+                    values
+                        .map(|v| match v {
+                            Value::Blob(_) => None,
+                            v => as_text(v),
+                        })
+                        .collect::<StringArray>(),
+                ),
+                SqliteKind::Any => Arc::new(values.map(as_text).collect::<StringArray>()),
+                SqliteKind::Blob => Arc::new(values.map(as_bytes).collect::<BinaryArray>()),
+                SqliteKind::Boolean => Arc::new(values.map(as_bool).collect::<BooleanArray>()),
+                SqliteKind::Date => Arc::new(
+                    values
+                        .map(|v| as_datetime(v).map(|t| (t.date() - epoch).num_days() as i32))
+                        .collect::<Date32Array>(),
+                ),
+                SqliteKind::Timestamp => Arc::new(
+                    values
+                        .map(|v| as_datetime(v).map(|t| t.and_utc().timestamp_micros()))
+                        .collect::<TimestampMicrosecondArray>(),
+                ),
+            };
+            array
+        })
+        .collect::<Vec<_>>();
+
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    Ok(RecordBatch::try_new_with_options(schema, arrays, &options)?)
+}
+
+fn as_i64(v: &Value) -> Option<i64> {
+    match v {
+        Value::Integer(i) => Some(*i),
+        Value::Real(f) if f.fract() == 0.0 => Some(*f as i64),
+        _ => None,
+    }
+}
+
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Integer(i) => Some(*i as f64),
+        Value::Real(f) => Some(*f),
+        _ => None,
+    }
+}
+
+fn as_text(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::Integer(i) => Some(i.to_string()),
+        Value::Real(f) => Some(f.to_string()),
+        Value::Text(s) => Some(s.clone()),
+        Value::Blob(b) => Some(String::from_utf8_lossy(b).into_owned()),
+    }
+}
+
+fn as_bytes(v: &Value) -> Option<Vec<u8>> {
+    match v {
+        Value::Text(s) => Some(s.as_bytes().to_vec()),
+        Value::Blob(b) => Some(b.clone()),
+        _ => None,
+    }
+}
+
+fn as_bool(v: &Value) -> Option<bool> {
+    match v {
+        Value::Integer(i) => Some(*i != 0),
+        Value::Real(f) => Some(*f != 0.0),
+        Value::Text(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "1" | "t" | "true" | "y" | "yes" => Some(true),
+            "0" | "f" | "false" | "n" | "no" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Dates and times are stored as ISO 8601 text or Unix seconds.
+fn as_datetime(v: &Value) -> Option<NaiveDateTime> {
+    match v {
+        Value::Integer(secs) => DateTime::from_timestamp(*secs, 0).map(|t| t.naive_utc()),
+        Value::Text(s) => {
+            let s = s.trim();
+            [
+                "%Y-%m-%d %H:%M:%S%.f",
+                "%Y-%m-%dT%H:%M:%S%.f",
+                "%Y-%m-%d %H:%M",
+            ]
+            .iter()
+            .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Array, AsArray},
+        datatypes::Int64Type,
+    };
+    use datafusion::prelude::{col, lit, SessionContext};
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    fn database() -> anyhow::Result<NamedTempFile> {
+        let file = NamedTempFile::new()?;
+        let conn = Connection::open(file.path())?;
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER, name TEXT, score REAL, active BOOLEAN, born DATE, note);
+             INSERT INTO users VALUES
+                 (1, 'alice', 1.5, 1, '2000-01-02', 'x'),
+                 (2, 'bob', 2.5, 0, '2001-02-03', 2),
+                 (3, 'carol', 3.5, 1, NULL, NULL),
+                 ('n/a', 'dave', 4.5, 0, NULL, NULL),
+                 (4.5, 'erin', 5.5, 1, NULL, NULL);
+             CREATE VIEW active AS SELECT id, name FROM users WHERE active;",
+        )?;
+        Ok(file)
+    }
+
+    async fn query_ids(provider: SqliteTableProvider, sql: &str) -> anyhow::Result<Vec<i64>> {
+        let ctx = SessionContext::new();
+        ctx.register_table("users", Arc::new(provider))?;
+        let batches = ctx.sql(sql).await?.collect().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int64Type>().iter().flatten())
+            .collect())
+    }
+
+    #[test]
+    fn try_new_should_map_declared_types() -> anyhow::Result<()> {
+        let file = database()?;
+        let provider = SqliteTableProvider::try_new(file.path().to_str().unwrap(), "users")?;
+        let types = provider
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                DataType::Int64,
+                DataType::Utf8,
+                DataType::Float64,
+                DataType::Boolean,
+                DataType::Date32,
+                DataType::Utf8,
+            ]
+        );
+        assert!(SqliteTableProvider::try_new(file.path().to_str().unwrap(), "nope").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn tables_should_list_tables_and_views() -> anyhow::Result<()> {
+        let file = database()?;
+        assert_eq!(
+            SqliteTableProvider::tables(file.path().to_str().unwrap())?,
+            vec!["active", "users"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn filters_should_be_pushed_down_inexactly() -> anyhow::Result<()> {
+        let file = database()?;
+        let provider = SqliteTableProvider::try_new(file.path().to_str().unwrap(), "users")?;
+        let pushdown = |e: Expr| provider.supports_filters_pushdown(&[&e]).unwrap()[0].clone();
+        assert_eq!(
+            pushdown(col("id").gt(lit(1))),
+            TableProviderFilterPushDown::Inexact
+        );
+        for e in [
+            col("id").is_null(),
+            (col("id") / lit(2)).eq(lit(1)),
+            col("active").eq(lit(true)),
+            col("born").gt(lit("2000-06-01")),
+            col("note").eq(lit("x")),
+        ] {
+            assert_eq!(pushdown(e), TableProviderFilterPushDown::Unsupported);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_should_agree_with_datafusion() -> anyhow::Result<()> {
+        let file = database()?;
+        let path = file.path().to_str().unwrap();
+        // 'n/a' and 4.5 are not integers, SQLite still finds them greater
+        assert_eq!(
+            query_ids(
+                SqliteTableProvider::try_new(path, "users")?,
+                "SELECT id FROM users WHERE id > 1 ORDER BY id"
+            )
+            .await?,
+            vec![2, 3]
+        );
+        assert_eq!(
+            query_ids(
+                SqliteTableProvider::try_new(path, "users")?,
+                "SELECT count(*) FROM users WHERE id IS NULL"
+            )
+            .await?,
+            vec![2]
+        );
+        assert_eq!(
+            query_ids(
+                SqliteTableProvider::try_new(path, "users")?,
+                "SELECT id FROM users WHERE name <> 'alice' LIMIT 1"
+            )
+            .await?,
+            vec![2]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_should_read_views() -> anyhow::Result<()> {
+        let file = database()?;
+        let provider = SqliteTableProvider::try_new(file.path().to_str().unwrap(), "active")?;
+        let ctx = SessionContext::new();
+        ctx.register_table("active", Arc::new(provider))?;
+        let batches = ctx.sql("SELECT name FROM active").await?.collect().await?;
+        let names = batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_string::<i32>()
+                    .iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["alice", "carol", "erin"]);
+        assert_eq!(batches[0].column(0).null_count(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use unicode_width::UnicodeWidthStr;
+
+pub const DEFAULT_BINS: usize = 10;
+const BAR_WIDTH: usize = 50;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum BinMethod {
+    /// Bins of the same width between the min and max values
+    #[default]
+    #[value(name = "equal")]
+    EqualWidth,
+    /// Bins holding roughly the same number of values
+    #[value(name = "quantile")]
+    Quantile,
+}
+
+/// Value counts of a column, either per bin or per distinct value, rendered
+/// as an ASCII bar chart.
+pub struct Histogram {
+    column: String,
+    bars: Vec<(String, u64)>,
+}
+
+impl Histogram {
+    /// Bars for the bins between consecutive `edges`, given the number of
+    /// values in each bin index. `label` formats an edge in the column's type.
+    pub fn binned(
+        column: &str,
+        edges: &[f64],
+        counts: impl IntoIterator<Item = (usize, u64)>,
+        nulls: u64,
+        label: impl Fn(f64) -> String,
+    ) -> Self {
+        let n = edges.len().saturating_sub(1);
+        let mut bars = (0..n)
+            .map(|i| {
+                let close = if i + 1 == n { ']' } else { ')' };
+                let name = format!("[{}, {}{}", label(edges[i]), label(edges[i + 1]), close);
+                (name, 0)
+            })
+            .collect::<Vec<_>>();
+        for (i, count) in counts {
+            if let Some(bar) = bars.get_mut(i) {
+                bar.1 += count;
+            }
+        }
+        if nulls > 0 {
+            bars.push(("NULL".to_string(), nulls));
+        }
+        Self {
+            column: column.to_string(),
+            bars,
+        }
+    }
+
+    /// Bars for the most frequent values, plus one for all the other values
+    /// out of `total`.
+    pub fn top_values(column: &str, mut bars: Vec<(String, u64)>, total: u64) -> Self {
+        let other = total.saturating_sub(bars.iter().map(|(_, count)| count).sum());
+        if other > 0 {
+            bars.push(("(other)".to_string(), other));
+        }
+        Self {
+            column: column.to_string(),
+            bars,
+        }
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(max) = self.bars.iter().map(|(_, count)| *count).max() else {
+            return write!(f, "No values in {}", self.column);
+        };
+        let width = self.bars.iter().map(|(label, _)| label.width()).max();
+        let width = width.unwrap_or_default().max(self.column.width());
+        writeln!(f, "{} |", pad(&self.column, width))?;
+        for (label, count) in &self.bars {
+            let len = match max {
+                0 => 0,
+                max => ((*count as f64 / max as f64) * BAR_WIDTH as f64).ceil() as usize,
+            };
+            writeln!(f, "{} | {} {}", pad(label, width), "#".repeat(len), count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Right-aligns `text` to `width` columns of the terminal.
+fn pad(text: &str, width: usize) -> String {
+    format!("{}{text}", " ".repeat(width.saturating_sub(text.width())))
+}
+
+/// Edges of `bins` bins of the same width between `min` and `max`. A single
+/// value gets a single bin.
+pub fn equal_width_edges(min: f64, max: f64, bins: usize) -> Vec<f64> {
+    if min >= max {
+        return vec![min, max];
+    }
+    let width = (max - min) / bins as f64;
+    let mut edges = (0..bins)
+        .map(|i| min + width * i as f64)
+        .collect::<Vec<_>>();
+    edges.push(max);
+    edges
+}
+
+/// The quantiles splitting the values into `bins` bins of the same size.
+pub fn quantile_fractions(bins: usize) -> Vec<f64> {
+    (0..=bins).map(|i| i as f64 / bins as f64).collect()
+}
+
+/// Edges coming from quantiles repeat when values do; keep each one once.
+pub fn dedup_edges(mut edges: Vec<f64>) -> Vec<f64> {
+    edges.dedup();
+    if edges.len() == 1 {
+        edges.push(edges[0]);
+    }
+    edges
+}
+
+pub fn format_edge(v: f64) -> String {
+    if v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{}", v as i64)
+    } else {
+        format!("{:.2}", v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_should_align_labels_by_display_width() {
+        let bars = vec![("北京".to_string(), 2), ("Zürich".to_string(), 1)];
+        let hist = Histogram::top_values("city", bars, 3).to_string();
+        let lines = hist.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "  city |");
+        assert!(lines[1].starts_with("  北京 | "));
+        assert!(lines[2].starts_with("Zürich | "));
+    }
+}
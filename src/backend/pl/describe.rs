@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+use polars::prelude::*;
+
+use crate::backend::DescribeMethod;
+
+pub struct PolarsDescriber {
+    original: LazyFrame,
+    /// Columns the statistics are grouped by.
+    by: Vec<String>,
+    /// Every other column with the expression that maps it to a number.
+    columns: Vec<(String, DataType, Expr)>,
+    methods: Vec<DescribeMethod>,
+}
+
+impl PolarsDescriber {
+    pub fn try_new(
+        mut lf: LazyFrame,
+        methods: Vec<DescribeMethod>,
+        by: Vec<String>,
+    ) -> anyhow::Result<Self> {
+        let schema = lf.schema()?;
+        if let Some(name) = by.iter().find(|name| schema.get(name).is_none()) {
+            bail!("Group by column not found: {}", name);
+        }
+        let columns = schema
+            .iter()
+            .filter(|(name, _)| !by.iter().any(|b| b == name.as_str()))
+            .map(|(name, dt)| (name.to_string(), dt.clone(), transform(name, dt)))
+            .collect::<Vec<_>>();
+        Ok(Self {
+            original: lf,
+            by,
+            columns,
+            methods,
+        })
+    }
+
+    /// Computes every statistic in a single pass over the data (one row per
+    /// group), then pivots every row into one row per statistic. Columns that
+    /// aren't numbers are strings when their mode is requested, as it is
+    /// their most frequent value.
+    pub fn describe(&self) -> anyhow::Result<DataFrame> {
+        let mut seen = HashSet::new();
+        let mut expressions = Vec::new();
+        for method in &self.methods {
+            for (i, (name, dt, x)) in self.columns.iter().enumerate() {
+                let alias = agg_name(method, i);
+                if seen.insert(alias.clone()) {
+                    let expr = stat(*method, name, x.clone(), mode_value(name, dt, x));
+                    let expr = if self.is_text(dt) {
+                        expr.cast(DataType::String)
+                    } else {
+                        expr.cast(DataType::Float64)
+                    };
+                    expressions.push(expr.alias(&alias));
+                }
+            }
+        }
+        let stats = if self.by.is_empty() {
+            self.original.clone().select(expressions)
+        } else {
+            self.original
+                .clone()
+                .group_by(self.group_exprs())
+                .agg(expressions)
+        };
+        let stats = stats.collect()?;
+
+        let rows = self
+            .methods
+            .iter()
+            .enumerate()
+            .map(|(order, method)| {
+                let mut expressions = self.group_exprs();
+                expressions.extend(
+                    self.columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (name, _, _))| col(&agg_name(method, i)).alias(name)),
+                );
+                stats.clone().lazy().select(expressions).with_columns([
+                    lit(method.to_string()).alias("stats"),
+                    lit(order as u32).alias("order"),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let mut order = self.group_exprs();
+        order.push(col("order"));
+        let mut expressions = self.group_exprs();
+        expressions.push(col("stats"));
+        expressions.extend(self.columns.iter().map(|(name, _, _)| col(name)));
+        let df = concat(rows, UnionArgs::default())?
+            .sort_by_exprs(
+                order,
+                SortMultipleOptions::default().with_maintain_order(true),
+            )
+            .select(expressions)
+            .collect()?;
+        Ok(df)
+    }
+
+    fn group_exprs(&self) -> Vec<Expr> {
+        self.by.iter().map(|name| col(name)).collect()
+    }
+
+    fn is_text(&self, dt: &DataType) -> bool {
+        mode_value_is_raw(dt) && self.methods.contains(&DescribeMethod::Mode)
+    }
+}
+
+/// Maps a column to a number the same way the DataFusion describer does.
+pub(super) fn transform(name: &str, dt: &DataType) -> Expr {
+    let expr = match dt {
+        dt if dt.is_temporal() => col(name).to_physical(),
+        dt if dt.is_numeric() => col(name),
+        DataType::List(_) => col(name).list().len(),
+        _ => col(name).cast(DataType::String).str().len_chars(),
+    };
+    expr.cast(DataType::Float64)
+}
+
+/// What the mode of a column is taken on: numbers, physical for temporal
+/// columns, or the column itself. Polars takes no mode of lists, so theirs is
+/// the most frequent length.
+fn mode_value(name: &str, dt: &DataType, x: &Expr) -> Expr {
+    if mode_value_is_raw(dt) {
+        col(name)
+    } else {
+        x.clone()
+    }
+}
+
+fn mode_value_is_raw(dt: &DataType) -> bool {
+    !dt.is_temporal() && !dt.is_numeric() && !matches!(dt, DataType::List(_))
+}
+
+fn agg_name(method: &DescribeMethod, i: usize) -> String {
+    format!("{}__{}", method, i)
+}
+
+fn stat(method: DescribeMethod, name: &str, x: Expr, value: Expr) -> Expr {
+    match method {
+        DescribeMethod::Total => x.count(),
+        DescribeMethod::NullTotal => x.null_count(),
+        DescribeMethod::Mean => x.mean(),
+        DescribeMethod::Stddev => x.std(1),
+        DescribeMethod::Min => x.min(),
+        DescribeMethod::Max => x.max(),
+        DescribeMethod::Median => x.median(),
+        DescribeMethod::Percentile(p) => {
+            x.quantile(lit(p as f64 / 100.0), QuantileInterpolOptions::Linear)
+        }
+        // distinct values are counted on the original column, not on its length
+        DescribeMethod::Distinct => col(name).drop_nulls().n_unique(),
+        DescribeMethod::Mode => top(value),
+        DescribeMethod::ModeFreq => value.clone().eq(top(value)).sum(),
+        DescribeMethod::Skewness => x.skew(true),
+        DescribeMethod::Kurtosis => x.kurtosis(true, true),
+        DescribeMethod::ZeroTotal => x.eq(lit(0.0)).sum(),
+    }
+}
+
+fn top(x: Expr) -> Expr {
+    x.drop_nulls().mode().sort(Default::default()).first()
+}
@@ -0,0 +1,83 @@
+use polars::prelude::*;
+
+use crate::{
+    backend::display,
+    cli::{OutputFile, OutputFormat},
+    DisplayFormat, DisplayOpts, ReplOutput,
+};
+
+/// Writes `df` to `output`, returning a message with the rows written.
+pub fn write_df(df: &mut DataFrame, output: &OutputFile) -> anyhow::Result<String> {
+    let mut out = output.create()?;
+    match output.format {
+        OutputFormat::Parquet => {
+            ParquetWriter::new(&mut out).finish(df)?;
+        }
+        OutputFormat::Csv => CsvWriter::new(&mut out).finish(df)?,
+        OutputFormat::NdJson => JsonWriter::new(&mut out)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(df)?,
+        OutputFormat::Arrow => IpcWriter::new(&mut out).finish(df)?,
+    }
+    out.finish()?;
+    Ok(format!("Wrote {} rows to {}", df.height(), output.path))
+}
+
+/// Splits the rows of `df`, out of `total` rows in the result, into pages
+/// formatted for the REPL.
+pub fn page_df(df: &DataFrame, total: usize, opts: &DisplayOpts) -> anyhow::Result<ReplOutput> {
+    let pages = opts
+        .pages(df.height())
+        .into_iter()
+        .map(|(offset, len)| format_df(&mut df.slice(offset as i64, len), opts.format))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(ReplOutput::paged(pages, total, df.height()))
+}
+
+/// Formats `df` as text for the REPL.
+pub fn format_df(df: &mut DataFrame, format: DisplayFormat) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    match format {
+        DisplayFormat::Csv => CsvWriter::new(&mut buf).finish(df)?,
+        DisplayFormat::Tsv => CsvWriter::new(&mut buf).with_separator(b'\t').finish(df)?,
+        DisplayFormat::Json => JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::Json)
+            .finish(df)?,
+        DisplayFormat::NdJson => JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(df)?,
+        DisplayFormat::Table | DisplayFormat::Markdown | DisplayFormat::Vertical => {
+            let headers = df
+                .get_column_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            let rows = cells(df)?;
+            return Ok(match format {
+                DisplayFormat::Table => display::table(&headers, &rows),
+                DisplayFormat::Markdown => display::markdown(&headers, &rows),
+                _ => display::vertical(&headers, &rows),
+            });
+        }
+    }
+    Ok(String::from_utf8(buf)?)
+}
+
+fn cells(df: &DataFrame) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut rows = Vec::with_capacity(df.height());
+    for row in 0..df.height() {
+        let mut cells = Vec::with_capacity(df.width());
+        for series in df.get_columns() {
+            let cell = match series.get(row)? {
+                AnyValue::Null => String::new(),
+                v => v
+                    .get_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| v.to_string()),
+            };
+            cells.push(cell);
+        }
+        rows.push(cells);
+    }
+    Ok(rows)
+}
@@ -0,0 +1,168 @@
+use anyhow::anyhow;
+use polars::prelude::*;
+
+use crate::backend::{hist, BinMethod, Histogram};
+
+use super::describe::transform;
+
+/// Value counts of `column`: top values for strings and booleans, bins over
+/// the describe mapping of the column for everything else.
+pub fn histogram(
+    mut lf: LazyFrame,
+    column: &str,
+    bins: usize,
+    method: BinMethod,
+) -> anyhow::Result<Histogram> {
+    let schema = lf.schema()?;
+    let dt = schema
+        .get(column)
+        .ok_or_else(|| anyhow!("Column not found: {}", column))?;
+    match dt {
+        DataType::String | DataType::Boolean => top_values(lf, column, bins),
+        dt => binned(lf, column, dt, bins, method),
+    }
+}
+
+fn top_values(lf: LazyFrame, column: &str, k: usize) -> anyhow::Result<Histogram> {
+    let total = count_of(&lf.clone().select([len().alias("rows")]).collect()?, "rows")?;
+    let df = lf
+        .group_by([col(column).alias("value")])
+        .agg([len().alias("freq")])
+        .sort_by_exprs(
+            [col("freq"), col("value")],
+            SortMultipleOptions::default()
+                .with_order_descending_multi([true, false])
+                .with_nulls_last(true),
+        )
+        .limit(k as IdxSize)
+        .collect()?;
+    let values = df.column("value")?;
+    let freqs = df.column("freq")?.cast(&DataType::UInt64)?;
+    let freqs = freqs.u64()?;
+    let mut bars = Vec::new();
+    for i in 0..df.height() {
+        let label = match values.get(i)? {
+            AnyValue::Null => "NULL".to_string(),
+            v => v
+                .get_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| v.to_string()),
+        };
+        bars.push((label, freqs.get(i).unwrap_or_default()));
+    }
+    Ok(Histogram::top_values(column, bars, total))
+}
+
+fn binned(
+    lf: LazyFrame,
+    column: &str,
+    dt: &DataType,
+    bins: usize,
+    method: BinMethod,
+) -> anyhow::Result<Histogram> {
+    let x = transform(column, dt);
+    let mut expressions = vec![len().alias("rows"), x.clone().count().alias("values")];
+    match method {
+        BinMethod::EqualWidth => {
+            expressions.push(x.clone().min().alias("e0"));
+            expressions.push(x.clone().max().alias("e1"));
+        }
+        BinMethod::Quantile => {
+            let quantiles = hist::quantile_fractions(bins).into_iter().enumerate();
+            expressions.extend(quantiles.map(|(i, q)| {
+                x.clone()
+                    .quantile(lit(q), QuantileInterpolOptions::Linear)
+                    .alias(&format!("e{}", i))
+            }));
+        }
+    }
+    let stats = lf.clone().select(expressions).collect()?;
+    let nulls = count_of(&stats, "rows")? - count_of(&stats, "values")?;
+    let mut edges = Vec::new();
+    for i in 0..stats.width() - 2 {
+        if let Some(edge) = stats.column(&format!("e{}", i))?.f64()?.get(0) {
+            edges.push(edge);
+        }
+    }
+    let edges = match (method, edges.as_slice()) {
+        (_, []) => vec![],
+        (BinMethod::EqualWidth, [min, max]) => hist::equal_width_edges(*min, *max, bins),
+        _ => hist::dedup_edges(edges),
+    };
+
+    let counts = match (method, edges.is_empty()) {
+        (_, true) => vec![],
+        (BinMethod::EqualWidth, _) => equal_width_counts(lf, x, &edges)?,
+        (BinMethod::Quantile, _) => quantile_counts(lf, x, &edges)?,
+    };
+    let label = |v: f64| edge_label(v, dt);
+    Ok(Histogram::binned(column, &edges, counts, nulls, label))
+}
+
+/// Counts by the index of the bin values fall in, computed from the width;
+/// the last bin includes its upper edge.
+fn equal_width_counts(lf: LazyFrame, x: Expr, edges: &[f64]) -> anyhow::Result<Vec<(usize, u64)>> {
+    let n = edges.len() - 1;
+    // at least 1 past the first bin, where truncating floors
+    let width = edges[1] - edges[0];
+    let i = ((x.clone() - lit(edges[0])) / lit(width)).cast(DataType::Int64);
+    let bin = when(x.clone().gt_eq(lit(edges[n - 1])))
+        .then(lit(n as i64 - 1))
+        .when(x.clone().lt(lit(edges[1])))
+        .then(lit(0_i64))
+        .otherwise(i);
+    let df = lf
+        .filter(x.is_not_null())
+        .group_by([bin.alias("bin")])
+        .agg([len().alias("freq")])
+        .collect()?;
+    let bins = df.column("bin")?.i64()?;
+    let freqs = df.column("freq")?.cast(&DataType::UInt64)?;
+    Ok(bins
+        .into_iter()
+        .zip(freqs.u64()?)
+        .filter_map(|(bin, freq)| Some((bin? as usize, freq?)))
+        .collect())
+}
+
+/// Counts of the values below every inner edge, in a single pass; those of
+/// the bins are their differences.
+fn quantile_counts(lf: LazyFrame, x: Expr, edges: &[f64]) -> anyhow::Result<Vec<(usize, u64)>> {
+    let n = edges.len() - 1;
+    let mut expressions = (1..n)
+        .map(|i| {
+            x.clone()
+                .lt(lit(edges[i]))
+                .sum()
+                .alias(&format!("below{}", i))
+        })
+        .collect::<Vec<_>>();
+    expressions.push(x.count().alias(&format!("below{}", n)));
+    let df = lf.select(expressions).collect()?;
+    let mut counts = Vec::with_capacity(n);
+    let mut below = 0;
+    for i in 1..=n {
+        let upto = count_of(&df, &format!("below{}", i))?;
+        counts.push((i - 1, upto - below));
+        below = upto;
+    }
+    Ok(counts)
+}
+
+fn count_of(df: &DataFrame, name: &str) -> anyhow::Result<u64> {
+    let series = df.column(name)?.cast(&DataType::UInt64)?;
+    Ok(series.u64()?.get(0).unwrap_or_default())
+}
+
+/// Temporal edges are shown in the column's own type.
+fn edge_label(v: f64, dt: &DataType) -> String {
+    if dt.is_temporal() {
+        let value = Series::new("edge", [v as i64])
+            .cast(dt)
+            .and_then(|s| s.get(0).map(|v| v.to_string()));
+        if let Ok(value) = value {
+            return value;
+        }
+    }
+    hist::format_edge(v)
+}
@@ -0,0 +1,4 @@
+mod describe;
+mod export;
+mod hist;
+pub mod polars_backend;
@@ -0,0 +1,372 @@
+use std::{
+    io::{Cursor, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::bail;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use polars::{prelude::*, sql::SQLContext};
+
+use crate::{
+    backend::Histogram,
+    cli::{
+        open_decompressed, ColumnType, ConnectOpts, CsvOpts, DatasetConn, DescribeOpts, FileFormat,
+        HistOpts, Listing, OutputFile,
+    },
+    Backend, DisplayOpts, ReplDisplay, ReplError, ReplExport, ReplOutput, ReplResult,
+};
+
+use super::{
+    describe::PolarsDescriber,
+    export::{page_df, write_df},
+    hist::histogram,
+};
+
+pub struct PolarsBackend(SQLContext);
+
+impl Backend for PolarsBackend {
+    async fn connect(&mut self, opts: &ConnectOpts) -> anyhow::Result<()> {
+        let lf = match &opts.conn {
+            DatasetConn::Postgres(_) => {
+                bail!("Postgres connection is not supported by the polars engine")
+            }
+            DatasetConn::MySql(_) => {
+                bail!("MySQL connection is not supported by the polars engine")
+            }
+            DatasetConn::Csv(file_opts) => {
+                ensure_uncompressed(&file_opts.ext, file_opts.compression)?;
+                scan(
+                    &file_opts.filename,
+                    FileFormat::Csv,
+                    &file_opts.ext,
+                    &opts.csv,
+                )?
+            }
+            DatasetConn::Parquet(file_opts)
+                if file_opts.compression != FileCompressionType::UNCOMPRESSED =>
+            {
+                // parquet needs random access, so the file is decompressed in memory
+                let mut buf = Vec::new();
+                open_decompressed(&file_opts.filename, file_opts.compression)?
+                    .read_to_end(&mut buf)?;
+                ParquetReader::new(Cursor::new(buf)).finish()?.lazy()
+            }
+            DatasetConn::Parquet(file_opts) => scan(
+                &file_opts.filename,
+                FileFormat::Parquet,
+                &file_opts.ext,
+                &opts.csv,
+            )?,
+            DatasetConn::NdJson(file_opts) => {
+                ensure_uncompressed(&file_opts.ext, file_opts.compression)?;
+                scan(
+                    &file_opts.filename,
+                    FileFormat::NdJson,
+                    &file_opts.ext,
+                    &opts.csv,
+                )?
+            }
+            DatasetConn::Arrow(file_opts) => {
+                ensure_uncompressed(&file_opts.ext, file_opts.compression)?;
+                scan(
+                    &file_opts.filename,
+                    FileFormat::Arrow,
+                    &file_opts.ext,
+                    &opts.csv,
+                )?
+            }
+            DatasetConn::Avro(_) => bail!("Avro files are not supported by the polars engine"),
+            DatasetConn::Spreadsheet(_) => {
+                bail!("Spreadsheets are not supported by the polars engine")
+            }
+            DatasetConn::Sqlite(_) | DatasetConn::DuckDb(_) => {
+                bail!("Database files are not supported by the polars engine")
+            }
+            DatasetConn::Listing(path) => {
+                let compression = opts.compression.map(Into::into);
+                let listing = Listing::resolve(path, opts.format, compression)?;
+                ensure_uncompressed(&listing.ext, listing.compression)?;
+                scan_listing(&listing, &opts.csv)?
+            }
+            DatasetConn::Remote(_) => {
+                bail!("Remote datasets are not supported by the polars engine")
+            }
+            DatasetConn::File(path) => bail!("Cannot tell the format of {}, use --format", path),
+        };
+        self.0.register(&opts.name, lf);
+        Ok(())
+    }
+    async fn disconnect(&mut self, name: &str) -> anyhow::Result<()> {
+        self.0.unregister(name);
+        Ok(())
+    }
+    async fn list(&self) -> anyhow::Result<ReplResult> {
+        let mut tables = self.0.get_tables();
+        tables.sort();
+        let types = vec!["BASE TABLE"; tables.len()];
+        let df = df!("table_name" => tables, "table_type" => types)?;
+        Ok(df.into())
+    }
+    async fn describe(&self, opts: &DescribeOpts) -> anyhow::Result<ReplResult> {
+        let lf = self.table(&opts.name)?;
+        let describer = PolarsDescriber::try_new(lf, opts.methods(), opts.by.clone())?;
+        let df = blocking(move || describer.describe()).await?;
+        Ok(df.into())
+    }
+    async fn schema(&self, name: &str) -> anyhow::Result<ReplResult> {
+        let schema = self.table(name)?.schema()?;
+        let (names, types): (Vec<_>, Vec<_>) = schema
+            .iter()
+            .map(|(name, dt)| (name.to_string(), dt.to_string()))
+            .unzip();
+        let nullable = vec!["YES"; names.len()];
+        let df = df!(
+            "column_name" => names,
+            "data_type" => types,
+            "is_nullable" => nullable,
+        )?;
+        Ok(df.into())
+    }
+    async fn head(&self, name: &str, n: usize) -> anyhow::Result<ReplResult> {
+        let df = collect(self.table(name)?.limit(n as IdxSize)).await?;
+        Ok(df.into())
+    }
+    async fn hist(&self, opts: &HistOpts) -> anyhow::Result<Histogram> {
+        let lf = self.table(&opts.name)?;
+        let (column, bins, method) = (opts.column.clone(), opts.bins, opts.method);
+        blocking(move || histogram(lf, &column, bins, method)).await
+    }
+    async fn sql(&self, query: &str) -> anyhow::Result<ReplResult> {
+        let lf = self.0.clone().execute(query)?;
+        Ok(lf.into())
+    }
+}
+
+impl Default for PolarsBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PolarsBackend {
+    pub fn new() -> Self {
+        Self(SQLContext::new())
+    }
+
+    fn table(&self, name: &str) -> anyhow::Result<LazyFrame> {
+        self.0
+            .get_table_map()
+            .remove(name)
+            .ok_or_else(|| ReplError::UnknownDataset(name.to_string()).into())
+    }
+}
+
+/// Runs a polars computation on a blocking thread. Polars can't cancel one,
+/// so on Ctrl-C the REPL stops waiting for it while it runs to completion in
+/// the background.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+async fn collect(lf: LazyFrame) -> anyhow::Result<DataFrame> {
+    blocking(move || Ok(lf.collect()?)).await
+}
+
+fn ensure_uncompressed(ext: &str, compression: FileCompressionType) -> anyhow::Result<()> {
+    if compression != FileCompressionType::UNCOMPRESSED {
+        bail!(
+            "Compressed {} files are not supported by the polars engine",
+            ext
+        );
+    }
+    Ok(())
+}
+
+fn scan(
+    path: impl AsRef<Path>,
+    format: FileFormat,
+    ext: &str,
+    csv: &CsvOpts,
+) -> anyhow::Result<LazyFrame> {
+    let lf = match format {
+        FileFormat::Csv => scan_csv(path, ext, csv)?,
+        FileFormat::Parquet => LazyFrame::scan_parquet(path, Default::default())?,
+        FileFormat::NdJson => LazyJsonLineReader::new(path).finish()?,
+        FileFormat::Arrow => LazyFrame::scan_ipc(path, Default::default())?,
+        v => bail!("{} files are not supported by the polars engine", v),
+    };
+    Ok(lf)
+}
+
+fn scan_csv(path: impl AsRef<Path>, ext: &str, csv: &CsvOpts) -> anyhow::Result<LazyFrame> {
+    if csv.escape.is_some() {
+        bail!("Escape characters are not supported by the polars engine, quotes are escaped by doubling them");
+    }
+    let mut reader = LazyCsvReader::new(path)
+        .with_has_header(!csv.no_header)
+        .with_separator(csv.delimiter(ext))
+        .with_null_values(csv.null_value.clone().map(NullValues::AllColumnsSingle));
+    if let Some(quote) = csv.quote {
+        reader = reader.with_quote_char(Some(quote));
+    }
+    if let Some(rows) = csv.infer_rows {
+        reader = reader.with_infer_schema_length(Some(rows));
+    }
+    if let Some(schema) = &csv.schema {
+        let fields = schema
+            .0
+            .iter()
+            .map(|(name, t)| Field::new(name, polars_type(*t)));
+        reader = reader.with_dtype_overwrite(Some(Arc::new(Schema::from_iter(fields))));
+    }
+    Ok(reader.finish()?)
+}
+
+fn polars_type(t: ColumnType) -> DataType {
+    match t {
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Int32 => DataType::Int32,
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::Float32 => DataType::Float32,
+        ColumnType::Float64 => DataType::Float64,
+        ColumnType::Utf8 => DataType::String,
+        ColumnType::Date => DataType::Date,
+        ColumnType::Timestamp => DataType::Datetime(TimeUnit::Microseconds, None),
+    }
+}
+
+/// Scans the data files of a listing, with their partition values as columns,
+/// and stacks them.
+fn scan_listing(listing: &Listing, csv: &CsvOpts) -> anyhow::Result<LazyFrame> {
+    let frames = listing
+        .data_files()
+        .map(|file| {
+            let values = listing
+                .partition_values(file)
+                .into_iter()
+                .map(|(key, value)| match value {
+                    Some(value) => lit(value).alias(&key),
+                    None => lit(NULL).cast(DataType::String).alias(&key),
+                })
+                .collect::<Vec<_>>();
+            Ok(scan(file, listing.format, &listing.ext, csv)?.with_columns(values))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(concat(frames, UnionArgs::default())?)
+}
+
+impl ReplDisplay for DataFrame {
+    async fn display(self, opts: &DisplayOpts) -> anyhow::Result<ReplOutput> {
+        let df = self.head(Some(opts.row_cap()));
+        page_df(&df, self.height(), opts)
+    }
+}
+
+impl ReplDisplay for LazyFrame {
+    /// Runs the query once; rows past `max_rows` are counted but not shown.
+    async fn display(self, opts: &DisplayOpts) -> anyhow::Result<ReplOutput> {
+        collect(self).await?.display(opts).await
+    }
+}
+
+impl ReplExport for DataFrame {
+    async fn export(mut self, output: &OutputFile) -> anyhow::Result<String> {
+        write_df(&mut self, output)
+    }
+}
+
+impl ReplExport for LazyFrame {
+    async fn export(self, output: &OutputFile) -> anyhow::Result<String> {
+        write_df(&mut collect(self).await?, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    async fn connect(backend: &mut PolarsBackend, path: &str, name: &str) -> anyhow::Result<()> {
+        let opts = ConnectOpts::try_parse_from(["connect", path, "-n", name])?;
+        backend.connect(&opts.resolve()?).await
+    }
+
+    async fn frame(result: ReplResult) -> anyhow::Result<DataFrame> {
+        match result {
+            ReplResult::Polars(df) => Ok(df),
+            ReplResult::PolarsLazy(lf) => collect(lf).await,
+            _ => bail!("expected a polars result"),
+        }
+    }
+
+    fn strings(df: &DataFrame, column: &str) -> anyhow::Result<Vec<String>> {
+        let values = df.column(column)?.str()?.into_no_null_iter();
+        Ok(values.map(String::from).collect())
+    }
+
+    #[tokio::test]
+    async fn polars_backend_should_query_assets() -> anyhow::Result<()> {
+        let mut backend = PolarsBackend::new();
+        connect(&mut backend, "assets/juventus.csv", "j").await?;
+        connect(&mut backend, "assets/users.ndjson", "u").await?;
+        connect(&mut backend, "assets/sample.parquet", "p").await?;
+
+        let tables = frame(backend.list().await?).await?;
+        assert_eq!(strings(&tables, "table_name")?, ["j", "p", "u"]);
+
+        let schema = frame(backend.schema("j").await?).await?;
+        let names = ["name", "position", "dob", "nationality", "kit_number"];
+        assert_eq!(strings(&schema, "column_name")?, names);
+        assert_eq!(strings(&schema, "data_type")?[4], "i64");
+
+        let head = frame(backend.head("u", 3).await?).await?;
+        assert_eq!(head.height(), 3);
+        assert!(head.column("email").is_ok());
+        assert_eq!(frame(backend.head("p", 2).await?).await?.height(), 2);
+
+        let query = "select count(*) as n from j where position = 'Goalkeeper'";
+        let df = frame(backend.sql(query).await?).await?;
+        let n = df.column("n")?.cast(&DataType::Int64)?;
+        assert_eq!(n.i64()?.get(0), Some(4));
+
+        let opts = DescribeOpts::try_parse_from(["describe", "j", "-s", "count,max"])?;
+        let df = frame(backend.describe(&opts).await?).await?;
+        assert_eq!(strings(&df, "stats")?, ["Total", "Max"]);
+        let kit = df.column("kit_number")?.f64()?;
+        assert_eq!(
+            kit.into_iter().collect::<Vec<_>>(),
+            [Some(27.0), Some(77.0)]
+        );
+
+        backend.disconnect("u").await?;
+        assert!(backend.head("u", 1).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn polars_backend_should_reject_unsupported_sources() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let gz = dir.path().join("data.csv.gz");
+        std::fs::write(&gz, "")?;
+        let gz = gz.to_string_lossy();
+
+        let mut backend = PolarsBackend::new();
+        let sources = [
+            (
+                "postgres://u@localhost/db",
+                "Postgres connection is not supported",
+            ),
+            ("assets/players.xlsx", "Spreadsheets are not supported"),
+            (gz.as_ref(), "Compressed csv files are not supported"),
+        ];
+        for (source, error) in sources {
+            let err = connect(&mut backend, source, "t").await.unwrap_err();
+            assert!(err.to_string().contains(error), "{}: {}", source, err);
+        }
+        Ok(())
+    }
+}
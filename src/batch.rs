@@ -0,0 +1,111 @@
+//! Runs a script of REPL commands without the REPL.
+
+use clap::Parser;
+
+use crate::{ReplCommand, ReplContext, ReplError};
+
+/// Runs the commands of `script` one after the other, printing their output,
+/// and returns how many of them failed. Unless `keep_going` is set, stops at
+/// the first failure, and always at a command cancelled with Ctrl-C.
+pub fn run_script(ctx: &ReplContext, script: &str, keep_going: bool) -> usize {
+    let mut failed = 0;
+    for line in split_commands(script) {
+        let ret = parse(&line).map(|cmd| ctx.run(cmd));
+        let cancelled = matches!(ret, Ok(Err(ReplError::Cancelled)));
+        match ret.and_then(|ret| ret.map_err(|e| e.to_string())) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("Error in `{}`: {}", line, e);
+                failed += 1;
+                if !keep_going || cancelled {
+                    break;
+                }
+            }
+        }
+    }
+    failed
+}
+
+/// Parses a command with the grammar of the REPL.
+fn parse(line: &str) -> Result<ReplCommand, String> {
+    let words = shlex::split(line).ok_or_else(|| "Unbalanced quotes".to_string())?;
+    ReplCommand::try_parse_from(std::iter::once("taotie".to_string()).chain(words))
+        .map_err(|e| e.to_string().trim_end().to_string())
+}
+
+/// Commands are separated by `;` or new lines, except within quotes, so a
+/// quoted query can span several lines. Lines starting with `#` are comments.
+fn split_commands(script: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+    for c in script.chars() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('"'), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ';' | '\n') => {
+                commands.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    commands.push(current);
+    commands
+        .into_iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty() && !c.starts_with('#'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, Config, Engine};
+
+    #[test]
+    fn split_commands_should_split_outside_quotes() {
+        let script = r#"
+# connect first
+connect data.csv -n data; show
+sql "select ';' as sep,
+  '# not a comment' from data"
+sql 'select "a;b"' ;; head data
+sql "select \"x;\" from data"
+"#;
+        assert_eq!(
+            split_commands(script),
+            vec![
+                "connect data.csv -n data",
+                "show",
+                "sql \"select ';' as sep,\n  '# not a comment' from data\"",
+                "sql 'select \"a;b\"'",
+                "head data",
+                r#"sql "select \"x;\" from data""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_should_reject_unbalanced_quotes() {
+        assert!(parse("sql \"select 1").is_err());
+        assert!(parse("nope").is_err());
+        assert!(parse("show").is_err());
+        assert!(matches!(parse("show settings"), Ok(ReplCommand::Show(_))));
+    }
+
+    #[test]
+    fn run_script_should_count_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = Catalog::new(dir.path().join("catalog.json"));
+        let ctx = ReplContext::new(Engine::DataFusion, Config::default(), catalog);
+        let script = "show settings; set nope 1; head missing; set head_rows 3";
+        assert_eq!(run_script(&ctx, script, false), 1);
+        assert_eq!(run_script(&ctx, script, true), 2);
+        assert_eq!(run_script(&ctx, "show settings; set head_rows 3", false), 0);
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use clap::{ArgMatches, Parser};
+
+use crate::{backend::BackendRegistry, CmdExector, ReplContext, ReplDisplay, ReplMsg, ReplOutput};
+
+#[derive(Debug, Parser)]
+pub struct CatalogOpts;
+
+pub fn catalog(
+    _args: ArgMatches,
+    ctx: &mut ReplContext,
+) -> reedline_repl_rs::Result<Option<String>> {
+    let (msg, rx) = ReplMsg::new(CatalogOpts);
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for CatalogOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let datasets = backend.datasets();
+        let names: ArrayRef = Arc::new(StringArray::from_iter_values(
+            datasets.iter().map(|d| d.name.as_str()),
+        ));
+        let kinds: ArrayRef = Arc::new(StringArray::from_iter_values(
+            datasets.iter().map(|d| d.conn.kind()),
+        ));
+        let sources: ArrayRef = Arc::new(StringArray::from_iter_values(
+            datasets.iter().map(|d| d.conn.redacted_source()),
+        ));
+        let tables: ArrayRef = Arc::new(StringArray::from_iter(
+            datasets.iter().map(|d| d.table.as_deref()),
+        ));
+        let batch = RecordBatch::try_from_iter([
+            ("name", names),
+            ("kind", kinds),
+            ("source", sources),
+            ("table", tables),
+        ])?;
+
+        batch.display(backend.display_opts()).await
+    }
+}
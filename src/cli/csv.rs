@@ -0,0 +1,192 @@
+use std::fmt;
+
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+/// How CSV files are read.
+#[derive(Debug, Clone, Default, Args, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOpts {
+    #[arg(
+        long,
+        value_parser = verify_char,
+        help = "Field delimiter, ',' by default and a tab for .tsv files"
+    )]
+    pub delimiter: Option<u8>,
+
+    #[arg(
+        long,
+        help = "The file has no header row, columns are named column_1, column_2, ..."
+    )]
+    pub no_header: bool,
+
+    #[arg(long, value_parser = verify_char, help = "Quote character, '\"' by default")]
+    pub quote: Option<u8>,
+
+    #[arg(long, value_parser = verify_char, help = "Escape character within quotes")]
+    pub escape: Option<u8>,
+
+    #[arg(
+        long,
+        help = "Value read as null; columns holding it are read as strings unless typed with --schema"
+    )]
+    pub null_value: Option<String>,
+
+    #[arg(long, help = "Rows read to infer the column types")]
+    pub infer_rows: Option<usize>,
+
+    #[arg(
+        long,
+        value_parser = verify_schema,
+        help = "Types of some of the columns, like 'id:int64,day:date'"
+    )]
+    pub schema: Option<SchemaOverride>,
+}
+
+impl CsvOpts {
+    pub fn delimiter(&self, ext: &str) -> u8 {
+        match (self.delimiter, ext) {
+            (Some(delimiter), _) => delimiter,
+            (None, "tsv") => b'\t',
+            (None, _) => b',',
+        }
+    }
+}
+
+/// Column types given on the command line, in place of the inferred ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SchemaOverride(pub Vec<(String, ColumnType)>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Boolean,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Utf8,
+    Date,
+    Timestamp,
+}
+
+impl TryFrom<&str> for ColumnType {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.to_ascii_lowercase().as_str() {
+            "bool" | "boolean" => Ok(ColumnType::Boolean),
+            "int" | "int32" | "integer" => Ok(ColumnType::Int32),
+            "bigint" | "int64" | "long" => Ok(ColumnType::Int64),
+            "float" | "float32" | "real" => Ok(ColumnType::Float32),
+            "double" | "float64" => Ok(ColumnType::Float64),
+            "string" | "utf8" | "text" | "varchar" => Ok(ColumnType::Utf8),
+            "date" => Ok(ColumnType::Date),
+            "timestamp" | "datetime" => Ok(ColumnType::Timestamp),
+            v => Err(format!("Unsupported column type: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnType::Boolean => write!(f, "boolean"),
+            ColumnType::Int32 => write!(f, "int32"),
+            ColumnType::Int64 => write!(f, "int64"),
+            ColumnType::Float32 => write!(f, "float32"),
+            ColumnType::Float64 => write!(f, "float64"),
+            ColumnType::Utf8 => write!(f, "utf8"),
+            ColumnType::Date => write!(f, "date"),
+            ColumnType::Timestamp => write!(f, "timestamp"),
+        }
+    }
+}
+
+impl SchemaOverride {
+    pub fn get(&self, name: &str) -> Option<ColumnType> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, t)| *t)
+    }
+}
+
+impl TryFrom<String> for SchemaOverride {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        verify_schema(&s)
+    }
+}
+
+impl From<SchemaOverride> for String {
+    fn from(schema: SchemaOverride) -> Self {
+        schema
+            .0
+            .iter()
+            .map(|(name, t)| format!("{}:{}", name, t))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn verify_char(s: &str) -> Result<u8, String> {
+    let s = match s {
+        "\\t" | "tab" => "\t",
+        s => s,
+    };
+    match s.as_bytes() {
+        [b] if b.is_ascii() => Ok(*b),
+        _ => Err(format!("Expected a single ASCII character, got: {}", s)),
+    }
+}
+
+fn verify_schema(s: &str) -> Result<SchemaOverride, String> {
+    s.split(',')
+        .map(|column| {
+            let (name, t) = column
+                .rsplit_once(':')
+                .ok_or_else(|| format!("Expected name:type, got: {}", column))?;
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(format!("Expected name:type, got: {}", column));
+            }
+            Ok((name.to_string(), ColumnType::try_from(t.trim())?))
+        })
+        .collect::<Result<_, String>>()
+        .map(SchemaOverride)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_schema_should_read_types() {
+        let schema = verify_schema("id:int64, day : Date,a:b:text").unwrap();
+        assert_eq!(
+            schema.0,
+            vec![
+                ("id".to_string(), ColumnType::Int64),
+                ("day".to_string(), ColumnType::Date),
+                ("a:b".to_string(), ColumnType::Utf8),
+            ]
+        );
+        assert_eq!(String::from(schema), "id:int64,day:date,a:b:utf8");
+    }
+
+    #[test]
+    fn verify_schema_should_reject_bad_strings() {
+        for s in ["", "id", "id:", ":int", "id:int,", "id:uuid", "id:int;day"] {
+            assert!(verify_schema(s).is_err(), "{} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn verify_char_should_take_one_ascii_character() {
+        assert_eq!(verify_char(";"), Ok(b';'));
+        assert_eq!(verify_char("\\t"), Ok(b'\t'));
+        assert_eq!(verify_char("tab"), Ok(b'\t'));
+        assert!(verify_char("").is_err());
+        assert!(verify_char(";;").is_err());
+        assert!(verify_char("é").is_err());
+    }
+}
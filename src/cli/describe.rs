@@ -1,11 +1,48 @@
 use clap::{ArgMatches, Parser};
 
-use crate::{CmdExector, ReplContext, ReplDisplay, ReplMsg};
+use super::{verify_output, OutputFile};
+use crate::{
+    backend::{BackendRegistry, DescribeMethod},
+    Backend, CmdExector, ReplContext, ReplMsg, ReplOutput,
+};
 
 #[derive(Debug, Parser)]
 pub struct DescribeOpts {
     #[arg(help = "Name of the dataset")]
     pub name: String,
+
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        help = "Percentiles to add, e.g. 25,50,75,99"
+    )]
+    pub percentiles: Vec<u8>,
+
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "Statistics to compute, e.g. count,null,mean,std,min,max,median,p95,distinct,mode,freq,skew,kurt,zero"
+    )]
+    pub stats: Vec<DescribeMethod>,
+
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "Columns to group the statistics by"
+    )]
+    pub by: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        value_parser = verify_output,
+        help = "Write the result to a parquet, csv, ndjson or arrow file, optionally .gz, .bz2, .xz or .zst"
+    )]
+    pub output: Option<OutputFile>,
 }
 
 pub fn describe(
@@ -16,20 +53,62 @@ pub fn describe(
         .get_one::<String>("name")
         .expect("export name")
         .to_owned();
-    let (msg, rx) = ReplMsg::new(DescribeOpts::new(name));
+    let percentiles = args
+        .get_many::<u8>("percentiles")
+        .map(|p| p.copied().collect())
+        .unwrap_or_default();
+    let stats = args
+        .get_many::<DescribeMethod>("stats")
+        .map(|s| s.copied().collect())
+        .unwrap_or_default();
+    let by = args
+        .get_many::<String>("by")
+        .map(|b| b.cloned().collect())
+        .unwrap_or_default();
+    let output = args.get_one::<OutputFile>("output").cloned();
+    let (msg, rx) = ReplMsg::new(DescribeOpts::new(name, percentiles, stats, by, output));
     Ok(ctx.send(msg, rx))
 }
 
 impl DescribeOpts {
-    pub fn new(name: String) -> Self {
-        Self { name }
+    pub fn new(
+        name: String,
+        percentiles: Vec<u8>,
+        stats: Vec<DescribeMethod>,
+        by: Vec<String>,
+        output: Option<OutputFile>,
+    ) -> Self {
+        Self {
+            name,
+            percentiles,
+            stats,
+            by,
+            output,
+        }
+    }
+
+    /// The requested statistics, or the default ones when none were given,
+    /// followed by the requested percentiles.
+    pub fn methods(&self) -> Vec<DescribeMethod> {
+        let mut methods = if self.stats.is_empty() {
+            DescribeMethod::defaults()
+        } else {
+            self.stats.clone()
+        };
+        methods.extend(
+            self.percentiles
+                .iter()
+                .map(|p| DescribeMethod::Percentile(*p)),
+        );
+        methods
     }
 }
 
 impl CmdExector for DescribeOpts {
-    async fn execute<T: crate::Backend>(self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.describe(&self.name).await?;
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let df = backend.describe(&self).await?;
 
-        df.display().await
+        df.output(self.output.as_ref(), backend.display_opts())
+            .await
     }
 }
@@ -0,0 +1,35 @@
+use clap::{ArgMatches, Parser};
+
+use crate::{backend::BackendRegistry, CmdExector, ReplContext, ReplMsg, ReplOutput};
+
+#[derive(Debug, Parser)]
+pub struct DisconnectOpts {
+    #[arg(help = "Name of the dataset")]
+    pub name: String,
+}
+
+impl DisconnectOpts {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+pub fn disconnect(
+    args: ArgMatches,
+    ctx: &mut ReplContext,
+) -> reedline_repl_rs::Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("export name")
+        .to_owned();
+    let (msg, rx) = ReplMsg::new(DisconnectOpts::new(name));
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for DisconnectOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        backend.disconnect(&self.name).await?;
+
+        Ok(format!("Disconnected from dataset: {}", self.name).into())
+    }
+}
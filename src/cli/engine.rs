@@ -0,0 +1,36 @@
+use clap::{ArgMatches, Parser};
+
+use crate::{backend::BackendRegistry, CmdExector, Engine, ReplContext, ReplMsg, ReplOutput};
+
+#[derive(Debug, Parser)]
+pub struct EngineOpts {
+    #[arg(value_enum, help = "Engine to switch to, omit to show the current one")]
+    pub engine: Option<Engine>,
+}
+
+impl EngineOpts {
+    pub fn new(engine: Option<Engine>) -> Self {
+        Self { engine }
+    }
+}
+
+pub fn engine(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<Option<String>> {
+    let engine = args.get_one::<Engine>("engine").copied();
+    let (msg, rx) = ReplMsg::new(EngineOpts::new(engine));
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for EngineOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let Some(engine) = self.engine else {
+            return Ok(format!("Current engine: {}", backend.engine()).into());
+        };
+
+        let failed = backend.switch(engine).await;
+        let mut ret = format!("Switched to engine: {}", engine);
+        for (name, e) in failed {
+            ret.push_str(&format!("\nDataset {} is not available: {}", name, e));
+        }
+        Ok(ret.into())
+    }
+}
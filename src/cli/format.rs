@@ -0,0 +1,37 @@
+use clap::{ArgMatches, Parser};
+
+use crate::{
+    backend::BackendRegistry, CmdExector, DisplayFormat, ReplContext, ReplMsg, ReplOutput,
+};
+
+#[derive(Debug, Parser)]
+pub struct FormatOpts {
+    #[arg(
+        value_enum,
+        help = "Format to display results in, omit to show the current one"
+    )]
+    pub format: Option<DisplayFormat>,
+}
+
+impl FormatOpts {
+    pub fn new(format: Option<DisplayFormat>) -> Self {
+        Self { format }
+    }
+}
+
+pub fn format(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<Option<String>> {
+    let format = args.get_one::<DisplayFormat>("format").copied();
+    let (msg, rx) = ReplMsg::new(FormatOpts::new(format));
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for FormatOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let Some(format) = self.format else {
+            return Ok(format!("Current format: {}", backend.display_opts().format).into());
+        };
+
+        backend.set_format(format);
+        Ok(format!("Display format set to: {}", format).into())
+    }
+}
@@ -1,6 +1,7 @@
 use clap::{ArgMatches, Parser};
 
-use crate::{CmdExector, ReplContext, ReplDisplay, ReplMsg};
+use super::{verify_output, OutputFile};
+use crate::{backend::BackendRegistry, Backend, CmdExector, ReplContext, ReplMsg, ReplOutput};
 
 #[derive(Debug, Parser)]
 pub struct HeadOpts {
@@ -9,11 +10,19 @@ pub struct HeadOpts {
 
     #[arg(short, long, help = "Number of rows to show")]
     pub n: Option<usize>,
+
+    #[arg(
+        short,
+        long,
+        value_parser = verify_output,
+        help = "Write the result to a parquet, csv, ndjson or arrow file, optionally .gz, .bz2, .xz or .zst"
+    )]
+    pub output: Option<OutputFile>,
 }
 
 impl HeadOpts {
-    pub fn new(name: String, n: Option<usize>) -> Self {
-        Self { name, n }
+    pub fn new(name: String, n: Option<usize>, output: Option<OutputFile>) -> Self {
+        Self { name, n, output }
     }
 }
 
@@ -23,14 +32,17 @@ pub fn head(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result
         .expect("export name")
         .to_owned();
     let n = args.get_one::<usize>("n").copied();
-    let (msg, rx) = ReplMsg::new(HeadOpts::new(name, n));
+    let output = args.get_one::<OutputFile>("output").cloned();
+    let (msg, rx) = ReplMsg::new(HeadOpts::new(name, n, output));
     Ok(ctx.send(msg, rx))
 }
 
 impl CmdExector for HeadOpts {
-    async fn execute<T: crate::Backend>(self, backend: &mut T) -> anyhow::Result<String> {
-        let df = backend.head(&self.name, self.n.unwrap_or(5)).await?;
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let n = self.n.unwrap_or(backend.config().head_rows);
+        let df = backend.head(&self.name, n).await?;
 
-        df.display().await
+        df.output(self.output.as_ref(), backend.display_opts())
+            .await
     }
 }
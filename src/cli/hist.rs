@@ -0,0 +1,70 @@
+use anyhow::bail;
+use clap::{ArgMatches, Parser};
+
+use crate::{
+    backend::{BackendRegistry, BinMethod, DEFAULT_BINS},
+    Backend, CmdExector, ReplContext, ReplMsg, ReplOutput,
+};
+
+#[derive(Debug, Parser)]
+pub struct HistOpts {
+    #[arg(help = "Name of the dataset")]
+    pub name: String,
+
+    #[arg(help = "Column to show the distribution of")]
+    pub column: String,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = DEFAULT_BINS,
+        help = "Number of bins, or of top values for string columns"
+    )]
+    pub bins: usize,
+
+    #[arg(short, long, value_enum, default_value_t = BinMethod::default(), help = "How to bin numeric and temporal columns")]
+    pub method: BinMethod,
+}
+
+pub fn hist(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<Option<String>> {
+    let name = args
+        .get_one::<String>("name")
+        .expect("export name")
+        .to_owned();
+    let column = args
+        .get_one::<String>("column")
+        .expect("export column")
+        .to_owned();
+    let bins = args
+        .get_one::<usize>("bins")
+        .copied()
+        .unwrap_or(DEFAULT_BINS);
+    let method = args
+        .get_one::<BinMethod>("method")
+        .copied()
+        .unwrap_or_default();
+    let (msg, rx) = ReplMsg::new(HistOpts::new(name, column, bins, method));
+    Ok(ctx.send(msg, rx))
+}
+
+impl HistOpts {
+    pub fn new(name: String, column: String, bins: usize, method: BinMethod) -> Self {
+        Self {
+            name,
+            column,
+            bins,
+            method,
+        }
+    }
+}
+
+impl CmdExector for HistOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        if self.bins == 0 {
+            bail!("The number of bins must be at least 1");
+        }
+        let hist = backend.hist(&self).await?;
+
+        Ok(hist.to_string().into())
+    }
+}
@@ -1,6 +1,8 @@
 use clap::{ArgMatches, Parser};
 
-use crate::{CmdExector, ReplContext, ReplDisplay, ReplMsg};
+use crate::{
+    backend::BackendRegistry, Backend, CmdExector, ReplContext, ReplDisplay, ReplMsg, ReplOutput,
+};
 
 #[derive(Debug, Parser)]
 pub struct ListOpts;
@@ -11,9 +13,9 @@ pub fn list(_args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Resul
 }
 
 impl CmdExector for ListOpts {
-    async fn execute<T: crate::Backend>(self, backend: &mut T) -> anyhow::Result<String> {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
         let df = backend.list().await?;
 
-        df.display().await
+        df.display(backend.display_opts()).await
     }
 }
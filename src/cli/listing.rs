@@ -0,0 +1,216 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+
+use super::connect::{file_suffix, file_type, FileFormat};
+
+/// The files of a directory or glob pattern, read as a single table.
+#[derive(Debug, Clone)]
+pub struct Listing {
+    /// The directory or pattern the files were found with.
+    pub path: String,
+    /// Empty for remote listings.
+    pub files: Vec<PathBuf>,
+    pub format: FileFormat,
+    pub ext: String,
+    pub compression: FileCompressionType,
+    /// Hive-style partition columns, from `key=value` directories.
+    pub partitions: Vec<String>,
+}
+
+/// Whether a connection string names several files rather than one.
+pub fn is_listing(s: &str) -> bool {
+    s.contains(['*', '?', '[']) || Path::new(s).is_dir()
+}
+
+impl Listing {
+    /// Finds the files of `path` and the format they share, unless `format`
+    /// forces one; `compression` likewise.
+    pub fn resolve(
+        path: &str,
+        format: Option<FileFormat>,
+        compression: Option<FileCompressionType>,
+    ) -> anyhow::Result<Self> {
+        let (base, mut files) = match path.find(['*', '?', '[']) {
+            Some(i) => {
+                let base = path[..i].rfind('/').map_or("", |j| &path[..j]);
+                let files = glob::glob(path)?
+                    .filter_map(Result::ok)
+                    .filter(|p| p.is_file() && !is_hidden(p))
+                    .collect::<Vec<_>>();
+                (PathBuf::from(base), files)
+            }
+            None => {
+                let mut files = Vec::new();
+                walk(Path::new(path), &mut files)?;
+                (PathBuf::from(path), files)
+            }
+        };
+        files.sort();
+        if files.is_empty() {
+            bail!("No files found in {}", path);
+        }
+
+        let mut types = files
+            .iter()
+            .filter_map(|f| file_type(&f.to_string_lossy()).ok())
+            .collect::<Vec<_>>();
+        types.sort_by(|a, b| a.1.cmp(&b.1));
+        types.dedup_by(|a, b| a.1 == b.1 && a.2 == b.2);
+        let (format, ext, found) = match (format, types.as_slice()) {
+            (None, [(format, ext, compression)]) => (*format, ext.clone(), *compression),
+            (None, []) => bail!("Cannot infer the format of {}, use --format", path),
+            (None, types) => {
+                let found = types
+                    .iter()
+                    .map(|(_, ext, _)| ext.as_str())
+                    .collect::<Vec<_>>();
+                bail!(
+                    "Files of several types in {}: {}, use --format",
+                    path,
+                    found.join(", ")
+                )
+            }
+            // a forced format reads every file, whatever its extension
+            (Some(format), [(_, ext, compression)]) => (format, ext.clone(), *compression),
+            (Some(format), _) => (format, String::new(), FileCompressionType::UNCOMPRESSED),
+        };
+        let compression = compression.unwrap_or(found);
+
+        let partitions = files[0]
+            .strip_prefix(&base)
+            .unwrap_or(&files[0])
+            .parent()
+            .into_iter()
+            .flat_map(|dir| dir.components())
+            .filter_map(|c| partition(&c.as_os_str().to_string_lossy()))
+            .map(|(key, _)| key)
+            .collect();
+        Ok(Self {
+            path: path.to_string(),
+            files,
+            format,
+            ext,
+            compression,
+            partitions,
+        })
+    }
+
+    /// The files of a URL, which can't be listed up front: a single file, a
+    /// directory or a `*.ext` pattern, read as the files of the directory
+    /// ending with `.ext`.
+    pub fn remote(
+        url: &str,
+        format: Option<FileFormat>,
+        compression: Option<FileCompressionType>,
+    ) -> anyhow::Result<Self> {
+        let (dir, name) = url.rsplit_once('/').unwrap_or(("", url));
+        let (path, pattern) = match name.find(['*', '?', '[']) {
+            None => (url.to_string(), name),
+            Some(0) if !dir.contains(['*', '?', '[']) && !name[1..].contains(['*', '?', '[']) => {
+                (format!("{}/", dir), &name[1..])
+            }
+            Some(_) => bail!("Only `*.ext` patterns are supported in URLs, got: {}", url),
+        };
+        let named = file_type(&format!("files{}", pattern)).ok();
+        let (format, ext, named_compression) = match (format, named) {
+            (Some(format), Some((named, ext, compression))) if named == format => {
+                (format, ext, compression)
+            }
+            (Some(format), _) => (format, String::new(), FileCompressionType::UNCOMPRESSED),
+            (None, Some(named)) => named,
+            (None, None) => bail!("Cannot infer the format of {}, use --format", url),
+        };
+        Ok(Self {
+            path,
+            files: Vec::new(),
+            format,
+            ext,
+            compression: compression.unwrap_or(named_compression),
+            partitions: Vec::new(),
+        })
+    }
+
+    /// The files read, as DataFusion picks them: those ending with the
+    /// suffix of the format, or all of them when it is forced on files of
+    /// several types.
+    pub fn data_files(&self) -> impl Iterator<Item = &PathBuf> {
+        let suffix = file_suffix(&self.ext, self.compression);
+        self.files
+            .iter()
+            .filter(move |f| f.to_string_lossy().ends_with(&suffix))
+    }
+
+    /// Values of the partition columns for one of the files.
+    pub fn partition_values(&self, file: &Path) -> Vec<(String, Option<String>)> {
+        let values = file
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| dir.file_name())
+            .filter_map(|name| partition(&name.to_string_lossy()))
+            .collect::<Vec<_>>();
+        self.partitions
+            .iter()
+            .map(|key| {
+                let value = values
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.clone());
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
+
+fn partition(segment: &str) -> Option<(String, String)> {
+    let (key, value) = segment.split_once('=')?;
+    (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+}
+
+/// Markers like `_SUCCESS` and hidden files are not data.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy())
+        .is_some_and(|name| name.starts_with(['.', '_']))
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if is_hidden(&path) {
+            continue;
+        }
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_files_should_skip_other_types() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        for name in ["a.csv", "b.csv", "notes.txt"] {
+            fs::write(dir.path().join(name), "x\n1\n")?;
+        }
+        let listing = Listing::resolve(&dir.path().to_string_lossy(), None, None)?;
+        assert_eq!(listing.files.len(), 3);
+        assert_eq!(listing.data_files().count(), 2);
+
+        // a format forced on files of several types reads them all
+        fs::write(dir.path().join("c.json"), "{}\n")?;
+        let listing = Listing::resolve(&dir.path().to_string_lossy(), Some(FileFormat::Csv), None)?;
+        assert_eq!(listing.data_files().count(), 4);
+        Ok(())
+    }
+}
@@ -1,17 +1,41 @@
+mod catalog;
 mod connect;
+mod csv;
 mod describe;
+mod disconnect;
+mod engine;
+mod format;
 mod head;
+mod hist;
 mod list;
+mod listing;
+mod output;
+mod remote;
 mod schema;
+mod set;
+mod show;
+mod sniff;
 mod sql;
 
 use enum_dispatch::enum_dispatch;
 
-pub use connect::{connect, ConnectOpts, DatasetConn};
+pub use catalog::{catalog, CatalogOpts};
+pub use connect::{connect, file_suffix, ConnectOpts, DatasetConn, FileFormat, FileOpts};
+pub use csv::{ColumnType, CsvOpts};
 pub use describe::{describe, DescribeOpts};
+pub use disconnect::{disconnect, DisconnectOpts};
+pub use engine::{engine, EngineOpts};
+pub use format::{format, FormatOpts};
 pub use head::{head, HeadOpts};
+pub use hist::{hist, HistOpts};
 pub use list::{list, ListOpts};
+pub use listing::Listing;
+pub use output::{verify_output, OutputFile, OutputFormat, OutputWriter};
+pub use remote::RemoteOpts;
 pub use schema::{schema, SchemaOpts};
+pub use set::{set, SetOpts};
+pub use show::{show, ShowOpts};
+pub use sniff::open_decompressed;
 pub use sql::{sql, SqlOpts};
 
 use clap::Parser;
@@ -24,6 +48,13 @@ pub enum ReplCommand {
         about = "Connect to a dataset and register it to Taotie"
     )]
     Connect(ConnectOpts),
+    #[command(
+        name = "disconnect",
+        about = "Remove a dataset from Taotie and its catalog"
+    )]
+    Disconnect(DisconnectOpts),
+    #[command(name = "catalog", about = "List the datasets saved across sessions")]
+    Catalog(CatalogOpts),
     #[command(name = "list", about = "List all registered datasets")]
     List(ListOpts),
     #[command(name = "schema", about = "Describe the schema of dataset")]
@@ -32,6 +63,26 @@ pub enum ReplCommand {
     Describe(DescribeOpts),
     #[command(name = "head", about = "Show the first few rows of a dataset")]
     Head(HeadOpts),
+    #[command(name = "hist", about = "Show the distribution of a column")]
+    Hist(HistOpts),
     #[command(name = "sql", about = "Run a SQL query on a dataset")]
     Sql(SqlOpts),
+    #[command(name = "engine", about = "Show or switch the query engine")]
+    Engine(EngineOpts),
+    #[command(name = "format", about = "Show or set how results are displayed")]
+    Format(FormatOpts),
+    #[command(name = "set", about = "Change a setting for this session")]
+    Set(SetOpts),
+    #[command(name = "show", about = "Show the settings of this session")]
+    Show(ShowOpts),
+}
+
+impl ReplCommand {
+    /// The SQL a command runs as typed by the user, to point into on errors.
+    pub fn query(&self) -> Option<&str> {
+        match self {
+            ReplCommand::Sql(opts) => Some(&opts.query),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use bzip2::write::BzEncoder;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use flate2::write::GzEncoder;
+use xz2::write::XzEncoder;
+
+use super::connect::compression_type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Parquet,
+    Csv,
+    NdJson,
+    Arrow,
+}
+
+/// A file to write a result to, its format and compression coming from its
+/// extensions the same way they do for datasets.
+#[derive(Debug, Clone)]
+pub struct OutputFile {
+    pub path: String,
+    pub format: OutputFormat,
+    pub compression: FileCompressionType,
+}
+
+/// Writes to an output file, compressing on the fly if asked to.
+pub enum OutputWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Bzip2(BzEncoder<BufWriter<File>>),
+    Xz(XzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl OutputFile {
+    pub fn create(&self) -> anyhow::Result<OutputWriter> {
+        let file = BufWriter::new(File::create(&self.path)?);
+        let writer = match self.compression {
+            FileCompressionType::GZIP => {
+                OutputWriter::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+            }
+            FileCompressionType::BZIP2 => {
+                OutputWriter::Bzip2(BzEncoder::new(file, bzip2::Compression::default()))
+            }
+            FileCompressionType::XZ => OutputWriter::Xz(XzEncoder::new(file, 6)),
+            FileCompressionType::ZSTD => OutputWriter::Zstd(zstd::Encoder::new(file, 0)?),
+            _ => OutputWriter::Plain(file),
+        };
+        Ok(writer)
+    }
+}
+
+impl OutputWriter {
+    /// Writes the trailer of the compressed stream, if any, and flushes.
+    pub fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            OutputWriter::Plain(w) => w,
+            OutputWriter::Gzip(w) => w.finish()?,
+            OutputWriter::Bzip2(w) => w.finish()?,
+            OutputWriter::Xz(w) => w.finish()?,
+            OutputWriter::Zstd(w) => w.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputWriter::Plain(w) => w.write(buf),
+            OutputWriter::Gzip(w) => w.write(buf),
+            OutputWriter::Bzip2(w) => w.write(buf),
+            OutputWriter::Xz(w) => w.write(buf),
+            OutputWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(w) => w.flush(),
+            OutputWriter::Gzip(w) => w.flush(),
+            OutputWriter::Bzip2(w) => w.flush(),
+            OutputWriter::Xz(w) => w.flush(),
+            OutputWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+pub fn verify_output(s: &str) -> Result<OutputFile, String> {
+    let mut exts = s.rsplit('.');
+    let (ext, compression) = match (exts.next(), exts.next()) {
+        (Some(ext1), Some(ext2)) => match compression_type(ext1) {
+            Some(compression) => (ext2, compression),
+            None => (ext1, FileCompressionType::UNCOMPRESSED),
+        },
+        _ => return Err(format!("Invalid output path: {}", s)),
+    };
+    let format = match ext {
+        "parquet" => OutputFormat::Parquet,
+        "csv" => OutputFormat::Csv,
+        "json" | "jsonl" | "ndjson" => OutputFormat::NdJson,
+        "arrow" | "ipc" | "feather" => OutputFormat::Arrow,
+        v => return Err(format!("Unsupported output file type: {}", v)),
+    };
+    if format == OutputFormat::Parquet && compression != FileCompressionType::UNCOMPRESSED {
+        return Err("Parquet files are compressed internally, drop the extra extension".into());
+    }
+    Ok(OutputFile {
+        path: s.to_string(),
+        format,
+        compression,
+    })
+}
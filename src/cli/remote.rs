@@ -0,0 +1,24 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+/// Where remote datasets are read from. Credentials are taken from the
+/// environment or the AWS profile, never saved.
+#[derive(Debug, Clone, Default, Args, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteOpts {
+    #[arg(
+        long,
+        help = "Endpoint of an S3-compatible store, like http://localhost:9000 for MinIO"
+    )]
+    pub endpoint: Option<String>,
+
+    #[arg(long, help = "Region of the S3 bucket, AWS_REGION by default")]
+    pub region: Option<String>,
+}
+
+/// Whether a connection string is the URL of an object store.
+pub fn is_remote(s: &str) -> bool {
+    ["s3://", "s3a://", "http://", "https://"]
+        .iter()
+        .any(|scheme| s.starts_with(scheme))
+}
@@ -1,6 +1,8 @@
 use clap::{ArgMatches, Parser};
 
-use crate::{CmdExector, ReplContext, ReplDisplay, ReplMsg};
+use crate::{
+    backend::BackendRegistry, Backend, CmdExector, ReplContext, ReplDisplay, ReplMsg, ReplOutput,
+};
 
 #[derive(Debug, Parser)]
 pub struct SchemaOpts {
@@ -24,9 +26,9 @@ impl SchemaOpts {
 }
 
 impl CmdExector for SchemaOpts {
-    async fn execute<T: crate::Backend>(self, backend: &mut T) -> anyhow::Result<String> {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
         let df = backend.schema(&self.name).await?;
 
-        df.display().await
+        df.display(backend.display_opts()).await
     }
 }
@@ -0,0 +1,42 @@
+use clap::{ArgMatches, Parser};
+
+use crate::{backend::BackendRegistry, CmdExector, ReplContext, ReplMsg, ReplOutput};
+
+#[derive(Debug, Parser)]
+pub struct SetOpts {
+    #[arg(help = "Setting to change, see `show settings`")]
+    pub key: String,
+
+    #[arg(help = "New value of the setting")]
+    pub value: String,
+}
+
+impl SetOpts {
+    pub fn new(key: String, value: String) -> Self {
+        Self { key, value }
+    }
+}
+
+pub fn set(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<Option<String>> {
+    let key = args
+        .get_one::<String>("key")
+        .expect("export key")
+        .to_owned();
+    let value = args
+        .get_one::<String>("value")
+        .expect("export value")
+        .to_owned();
+    let (msg, rx) = ReplMsg::new(SetOpts::new(key, value));
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for SetOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        let failed = backend.set(&self.key, &self.value).await?;
+        let mut ret = format!("{} set to: {}", self.key, self.value);
+        for (name, e) in failed {
+            ret.push_str(&format!("\nDataset {} is not available: {}", name, e));
+        }
+        Ok(ret.into())
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use clap::{ArgMatches, Parser, ValueEnum};
+
+use crate::{backend::BackendRegistry, CmdExector, ReplContext, ReplDisplay, ReplMsg, ReplOutput};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ShowTarget {
+    /// Current value of every setting
+    #[value(name = "settings")]
+    Settings,
+}
+
+#[derive(Debug, Parser)]
+pub struct ShowOpts {
+    #[arg(value_enum, help = "What to show")]
+    pub target: ShowTarget,
+}
+
+impl ShowOpts {
+    pub fn new(target: ShowTarget) -> Self {
+        Self { target }
+    }
+}
+
+pub fn show(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<Option<String>> {
+    let target = *args.get_one::<ShowTarget>("target").expect("export target");
+    let (msg, rx) = ReplMsg::new(ShowOpts::new(target));
+    Ok(ctx.send(msg, rx))
+}
+
+impl CmdExector for ShowOpts {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
+        match self.target {
+            ShowTarget::Settings => {
+                let settings = backend.config().settings();
+                let keys: ArrayRef = Arc::new(StringArray::from_iter_values(
+                    settings.iter().map(|(key, _)| *key),
+                ));
+                let values: ArrayRef = Arc::new(StringArray::from_iter_values(
+                    settings.iter().map(|(_, value)| value.as_str()),
+                ));
+                let batch = RecordBatch::try_from_iter([("setting", keys), ("value", values)])?;
+
+                batch.display(backend.display_opts()).await
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Tells the compression and format of a file from its first bytes, for
+//! files whose name doesn't.
+
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+};
+
+use bzip2::read::BzDecoder;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use flate2::read::MultiGzDecoder;
+use xz2::read::XzDecoder;
+
+use super::FileFormat;
+
+const HEAD_SIZE: u64 = 4096;
+
+pub fn sniff(path: &str) -> anyhow::Result<(Option<FileFormat>, FileCompressionType)> {
+    let compression = compression_of(&head(File::open(path)?)?);
+    let head = head(open_decompressed(path, compression)?)?;
+    Ok((format_of(&head), compression))
+}
+
+/// Opens a file, decompressing it on the fly.
+pub fn open_decompressed(
+    path: &str,
+    compression: FileCompressionType,
+) -> anyhow::Result<Box<dyn Read>> {
+    let file = BufReader::new(File::open(path)?);
+    let reader: Box<dyn Read> = match compression {
+        FileCompressionType::GZIP => Box::new(MultiGzDecoder::new(file)),
+        FileCompressionType::BZIP2 => Box::new(BzDecoder::new(file)),
+        FileCompressionType::XZ => Box::new(XzDecoder::new(file)),
+        FileCompressionType::ZSTD => Box::new(zstd::Decoder::with_buffer(file)?),
+        _ => Box::new(file),
+    };
+    Ok(reader)
+}
+
+fn head(reader: impl Read) -> anyhow::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    reader.take(HEAD_SIZE).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+fn compression_of(head: &[u8]) -> FileCompressionType {
+    match head {
+        [0x1f, 0x8b, ..] => FileCompressionType::GZIP,
+        [b'B', b'Z', b'h', ..] => FileCompressionType::BZIP2,
+        [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => FileCompressionType::XZ,
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => FileCompressionType::ZSTD,
+        _ => FileCompressionType::UNCOMPRESSED,
+    }
+}
+
+/// Binary formats have magic bytes; text is taken for JSON lines when it
+/// starts with an object, for CSV otherwise.
+fn format_of(head: &[u8]) -> Option<FileFormat> {
+    if head.starts_with(b"PAR1") {
+        return Some(FileFormat::Parquet);
+    }
+    if head.starts_with(b"ARROW1") {
+        return Some(FileFormat::Arrow);
+    }
+    if head.starts_with(b"Obj\x01") {
+        return Some(FileFormat::Avro);
+    }
+    if head.starts_with(b"SQLite format 3\0") {
+        return Some(FileFormat::Sqlite);
+    }
+    if head.get(8..12) == Some(&b"DUCK"[..]) {
+        return Some(FileFormat::DuckDb);
+    }
+    let text = head
+        .iter()
+        .all(|b| !b.is_ascii_control() || b"\t\r\n".contains(b));
+    match head.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'{') if text => Some(FileFormat::NdJson),
+        Some(_) if text => Some(FileFormat::Csv),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use super::*;
+
+    #[test]
+    fn format_of_should_read_magic_bytes() {
+        let mut duckdb = vec![0; 8];
+        duckdb.extend_from_slice(b"DUCK\x40\0\0\0");
+        let cases: [(&[u8], Option<FileFormat>); 11] = [
+            (b"PAR1\x15\x04", Some(FileFormat::Parquet)),
+            (b"ARROW1\0\0", Some(FileFormat::Arrow)),
+            (b"Obj\x01\x04\x14", Some(FileFormat::Avro)),
+            (b"SQLite format 3\0\x10\0", Some(FileFormat::Sqlite)),
+            (&duckdb, Some(FileFormat::DuckDb)),
+            (b"\0\0\0\0\0\0\0\0DUC", None),
+            (b"  \n{\"id\": 1}\n", Some(FileFormat::NdJson)),
+            (b"id,name\n1,a\n", Some(FileFormat::Csv)),
+            (b"id\tname\r\n1\ta\r\n", Some(FileFormat::Csv)),
+            (b"\0\x01\x02", None),
+            (b" \n", None),
+        ];
+        for (head, format) in cases {
+            assert_eq!(format_of(head), format, "{:?}", head);
+        }
+    }
+
+    #[test]
+    fn compression_of_should_read_magic_bytes() {
+        let cases: [(&[u8], FileCompressionType); 6] = [
+            (b"\x1f\x8b\x08", FileCompressionType::GZIP),
+            (b"BZh91AY", FileCompressionType::BZIP2),
+            (b"\xfd7zXZ\0\0", FileCompressionType::XZ),
+            (b"\x28\xb5\x2f\xfd\x04", FileCompressionType::ZSTD),
+            (b"PAR1", FileCompressionType::UNCOMPRESSED),
+            (b"\x1f", FileCompressionType::UNCOMPRESSED),
+        ];
+        for (head, compression) in cases {
+            assert_eq!(compression_of(head), compression, "{:?}", head);
+        }
+    }
+
+    #[test]
+    fn sniff_should_look_into_compressed_files() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let data = b"{\"id\": 1}\n{\"id\": 2}\n";
+        let compress = |compression| -> anyhow::Result<Vec<u8>> {
+            Ok(match compression {
+                FileCompressionType::GZIP => {
+                    let mut w = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+                    w.write_all(data)?;
+                    w.finish()?
+                }
+                FileCompressionType::BZIP2 => {
+                    let mut w = bzip2::write::BzEncoder::new(Vec::new(), Default::default());
+                    w.write_all(data)?;
+                    w.finish()?
+                }
+                FileCompressionType::XZ => {
+                    let mut w = xz2::write::XzEncoder::new(Vec::new(), 6);
+                    w.write_all(data)?;
+                    w.finish()?
+                }
+                FileCompressionType::ZSTD => zstd::encode_all(&data[..], 0)?,
+                _ => data.to_vec(),
+            })
+        };
+        for (i, compression) in [
+            FileCompressionType::UNCOMPRESSED,
+            FileCompressionType::GZIP,
+            FileCompressionType::BZIP2,
+            FileCompressionType::XZ,
+            FileCompressionType::ZSTD,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            // no extension to tell either
+            let path = dir.path().join(format!("data{}", i));
+            fs::write(&path, compress(compression)?)?;
+            let (format, sniffed) = sniff(&path.to_string_lossy())?;
+            assert_eq!(sniffed, compression);
+            assert_eq!(format, Some(FileFormat::NdJson));
+        }
+        Ok(())
+    }
+}
@@ -1,16 +1,25 @@
 use clap::{ArgMatches, Parser};
 
-use crate::{CmdExector, ReplContext, ReplDisplay, ReplMsg};
+use super::{verify_output, OutputFile};
+use crate::{backend::BackendRegistry, Backend, CmdExector, ReplContext, ReplMsg, ReplOutput};
 
 #[derive(Debug, Parser)]
 pub struct SqlOpts {
     #[arg(help = "SQL query to run")]
     pub query: String,
+
+    #[arg(
+        short,
+        long,
+        value_parser = verify_output,
+        help = "Write the result to a parquet, csv, ndjson or arrow file, optionally .gz, .bz2, .xz or .zst"
+    )]
+    pub output: Option<OutputFile>,
 }
 
 impl SqlOpts {
-    pub fn new(query: String) -> Self {
-        Self { query }
+    pub fn new(query: String, output: Option<OutputFile>) -> Self {
+        Self { query, output }
     }
 }
 
@@ -19,14 +28,16 @@ pub fn sql(args: ArgMatches, ctx: &mut ReplContext) -> reedline_repl_rs::Result<
         .get_one::<String>("query")
         .expect("export query")
         .to_owned();
-    let (msg, rx) = ReplMsg::new(SqlOpts::new(query));
+    let output = args.get_one::<OutputFile>("output").cloned();
+    let (msg, rx) = ReplMsg::new(SqlOpts::new(query, output));
     Ok(ctx.send(msg, rx))
 }
 
 impl CmdExector for SqlOpts {
-    async fn execute<T: crate::Backend>(self, backend: &mut T) -> anyhow::Result<String> {
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput> {
         let df = backend.sql(&self.query).await?;
 
-        df.display().await
+        df.output(self.output.as_ref(), backend.display_opts())
+            .await
     }
 }
@@ -0,0 +1,220 @@
+//! Settings read from `~/.taotie/config.toml` at startup, some of which can
+//! be changed in a session with `set`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{cli::ConnectOpts, DisplayFormat, DisplayOpts};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Commands kept in the REPL history
+    pub history_size: usize,
+    pub banner: String,
+    /// Rows shown by `head` without `-n`
+    pub head_rows: usize,
+    #[serde(flatten)]
+    pub display: DisplayOpts,
+    /// Rows per batch read and produced by DataFusion
+    pub batch_size: usize,
+    /// Partitions DataFusion splits queries into, 0 for the number of cores
+    pub target_partitions: usize,
+    /// Memory DataFusion may use for a query, like "4GB"; unlimited if unset
+    pub memory_limit: Option<String>,
+    /// Datasets connected at startup
+    pub datasets: Vec<ConnectOpts>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            history_size: 1024,
+            banner: "Welcome to Taotie, your data analysis tool".to_string(),
+            head_rows: 5,
+            display: DisplayOpts::default(),
+            batch_size: 8192,
+            target_partitions: 0,
+            memory_limit: None,
+            datasets: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".taotie").join("config.toml"))
+    }
+
+    /// The config file if there is one, the defaults otherwise.
+    pub fn load() -> anyhow::Result<Self> {
+        match Self::path().filter(|path| path.exists()) {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Reads a config file, checking its values as `set` would.
+    fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+        if config.batch_size == 0 {
+            bail!(
+                "Invalid config file {}: batch_size must be positive",
+                path.display()
+            );
+        }
+        if let Some(limit) = &config.memory_limit {
+            parse_size(limit)?;
+        }
+        Ok(config)
+    }
+
+    /// The memory limit in bytes.
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+            .as_deref()
+            .and_then(|s| parse_size(s).ok())
+    }
+
+    /// Every setting with its current value.
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("history_size", self.history_size.to_string()),
+            ("banner", self.banner.clone()),
+            ("head_rows", self.head_rows.to_string()),
+            ("format", self.display.format.to_string()),
+            ("max_rows", self.display.max_rows.to_string()),
+            ("page_size", self.display.page_size.to_string()),
+            ("batch_size", self.batch_size.to_string()),
+            ("target_partitions", self.target_partitions.to_string()),
+            (
+                "memory_limit",
+                self.memory_limit.clone().unwrap_or_default(),
+            ),
+        ]
+    }
+
+    /// Changes a setting for the session.
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "history_size" | "banner" => {
+                bail!(
+                    "{} is only read at startup, change it in the config file",
+                    key
+                )
+            }
+            "head_rows" => self.head_rows = parse_number(key, value)?,
+            "format" => {
+                self.display.format =
+                    DisplayFormat::from_str(value, true).map_err(|e| anyhow!(e))?
+            }
+            "max_rows" => self.display.max_rows = parse_number(key, value)?,
+            "page_size" => self.display.page_size = parse_number(key, value)?,
+            "batch_size" => match parse_number(key, value)? {
+                0 => bail!("batch_size must be positive"),
+                n => self.batch_size = n,
+            },
+            "target_partitions" => self.target_partitions = parse_number(key, value)?,
+            "memory_limit" => {
+                self.memory_limit = match value {
+                    "" | "none" => None,
+                    v => {
+                        parse_size(v)?;
+                        Some(v.to_string())
+                    }
+                }
+            }
+            _ => bail!("Unknown setting: {}", key),
+        }
+        Ok(())
+    }
+}
+
+fn parse_number(key: &str, value: &str) -> anyhow::Result<usize> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("{} must be a number, got {}", key, value))
+}
+
+/// Parses sizes like "512MB", "4GiB" or "1073741824".
+fn parse_size(s: &str) -> anyhow::Result<usize> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().map_err(|_| anyhow!("Invalid size: {}", s))?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let scale = match unit.trim_end_matches("IB").trim_end_matches('B') {
+        "" => 1u64,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => bail!("Invalid size unit: {}", unit),
+    };
+    Ok((number * scale as f64) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_should_read_units() {
+        assert_eq!(parse_size("1073741824").unwrap(), 1 << 30);
+        assert_eq!(parse_size("512B").unwrap(), 512);
+        assert_eq!(parse_size("64k").unwrap(), 64 << 10);
+        assert_eq!(parse_size("512 mb").unwrap(), 512 << 20);
+        assert_eq!(parse_size("4GiB").unwrap(), 4 << 30);
+        assert_eq!(parse_size("1.5G").unwrap(), 3 << 29);
+        assert_eq!(parse_size(" 2TB ").unwrap(), 2 << 40);
+    }
+
+    #[test]
+    fn parse_size_should_reject_invalid_sizes() {
+        for size in ["", "GB", "-1GB", "1.2.3MB", "12XB", "4 GB extra"] {
+            assert!(parse_size(size).is_err(), "{} should be invalid", size);
+        }
+    }
+
+    #[test]
+    fn load_should_validate_values() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "batch_size = 1024\nmemory_limit = \"1GB\"\n")?;
+        let config = Config::load_from(&path)?;
+        assert_eq!(config.batch_size, 1024);
+        assert_eq!(config.memory_limit(), Some(1 << 30));
+        assert_eq!(config.head_rows, 5);
+
+        fs::write(&path, "batch_size = 0\n")?;
+        assert!(Config::load_from(&path).is_err());
+        fs::write(&path, "memory_limit = \"lots\"\n")?;
+        assert!(Config::load_from(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn set_should_validate_values() {
+        let mut config = Config::default();
+        config.set("memory_limit", "2GB").unwrap();
+        assert_eq!(config.memory_limit(), Some(2 << 30));
+        config.set("memory_limit", "none").unwrap();
+        assert_eq!(config.memory_limit(), None);
+
+        assert!(config.set("memory_limit", "2XB").is_err());
+        assert!(config.set("batch_size", "0").is_err());
+        assert!(config.set("head_rows", "many").is_err());
+        assert!(config.set("banner", "hi").is_err());
+        assert!(config.set("nope", "1").is_err());
+        assert_eq!(config.batch_size, 8192);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fmt, io};
+
+use datafusion::error::DataFusionError;
+use polars::error::PolarsError;
+
+/// Why a command failed, as reported back to the REPL.
+#[derive(Debug)]
+pub enum ReplError {
+    UnknownDataset(String),
+    /// The query did not parse. The position is the 1-based line and column
+    /// of the offending token, when the parser reports one.
+    SqlParse {
+        message: String,
+        sql: Option<String>,
+        position: Option<(usize, usize)>,
+    },
+    /// The query parsed but could not be planned: unknown column, type
+    /// mismatch, ...
+    Plan(String),
+    Io(String),
+    /// The command was stopped with Ctrl-C.
+    Cancelled,
+    Other(anyhow::Error),
+}
+
+impl ReplError {
+    /// Classifies an error from a command. `sql` is the query it ran, if any,
+    /// to point at the offending token of a parse error.
+    pub fn new(e: anyhow::Error, sql: Option<&str>) -> Self {
+        let e = match e.downcast::<ReplError>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        if let Some(err) = e.downcast_ref::<DataFusionError>() {
+            match err.find_root() {
+                DataFusionError::SQL(err, _) => return Self::parse_error(err.to_string(), sql),
+                DataFusionError::Plan(msg) => return Self::Plan(msg.clone()),
+                DataFusionError::SchemaError(err, _) => return Self::Plan(err.to_string()),
+                DataFusionError::IoError(err) => return Self::Io(err.to_string()),
+                DataFusionError::ObjectStore(err) => return Self::Io(err.to_string()),
+                _ => {}
+            }
+        }
+        if let Some(err) = e.downcast_ref::<PolarsError>() {
+            match err {
+                PolarsError::SQLSyntax(msg) => return Self::parse_error(msg.to_string(), sql),
+                PolarsError::SQLInterface(msg)
+                | PolarsError::ColumnNotFound(msg)
+                | PolarsError::SchemaMismatch(msg)
+                | PolarsError::InvalidOperation(msg) => return Self::Plan(msg.to_string()),
+                PolarsError::IO { .. } => return Self::Io(err.to_string()),
+                _ => {}
+            }
+        }
+        if let Some(err) = e.downcast_ref::<io::Error>() {
+            return Self::Io(err.to_string());
+        }
+        Self::Other(e)
+    }
+
+    /// sqlparser ends its messages with " at Line: L, Column C"; move that
+    /// into the position.
+    fn parse_error(message: String, sql: Option<&str>) -> Self {
+        let message = message
+            .strip_prefix("sql parser error: ")
+            .unwrap_or(&message)
+            .to_string();
+        let (message, position) = match message.rsplit_once(" at Line: ") {
+            Some((msg, loc)) => match loc.split_once(", Column ") {
+                Some((line, column)) => match (line.parse(), column.parse()) {
+                    (Ok(line), Ok(column)) => (msg.to_string(), Some((line, column))),
+                    _ => (message.clone(), None),
+                },
+                None => (message.clone(), None),
+            },
+            None => (message.clone(), None),
+        };
+        Self::SqlParse {
+            message,
+            sql: sql.map(str::to_string),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplError::UnknownDataset(name) => write!(f, "Unknown dataset: {}", name),
+            ReplError::SqlParse {
+                message,
+                sql,
+                position,
+            } => {
+                write!(f, "SQL parse error: {}", message)?;
+                match (sql, position) {
+                    (Some(sql), Some((line, column))) => {
+                        let text = sql.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+                        let pad = text
+                            .chars()
+                            .take(column.saturating_sub(1))
+                            .map(|c| if c == '\t' { '\t' } else { ' ' })
+                            .collect::<String>();
+                        write!(f, "\n  {}\n  {}^", text, pad)
+                    }
+                    (None, Some((line, column))) => {
+                        write!(f, " (line {}, column {})", line, column)
+                    }
+                    _ => Ok(()),
+                }
+            }
+            ReplError::Plan(msg) => write!(f, "Planning error: {}", msg),
+            ReplError::Io(msg) => write!(f, "IO error: {}", msg),
+            ReplError::Cancelled => write!(f, "Query cancelled"),
+            ReplError::Other(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_should_take_the_position_out_of_the_message() {
+        let err = ReplError::parse_error(
+            "sql parser error: Expected end of statement, found: frm at Line: 1, Column 10"
+                .to_string(),
+            Some("select a frm t"),
+        );
+        match &err {
+            ReplError::SqlParse {
+                message, position, ..
+            } => {
+                assert_eq!(message, "Expected end of statement, found: frm");
+                assert_eq!(*position, Some((1, 10)));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "SQL parse error: Expected end of statement, found: frm\n  select a frm t\n           ^"
+        );
+    }
+
+    #[test]
+    fn parse_error_should_point_into_the_right_line() {
+        let err = ReplError::parse_error(
+            "Expected an expression, found: FROM at Line: 2, Column 4".to_string(),
+            Some("select a,\n\tb, from t"),
+        );
+        assert_eq!(
+            err.to_string(),
+            "SQL parse error: Expected an expression, found: FROM\n  \tb, from t\n  \t  ^"
+        );
+    }
+
+    #[test]
+    fn parse_error_should_keep_unknown_positions() {
+        let err = ReplError::parse_error("unexpected end of input".to_string(), Some("select"));
+        assert_eq!(err.to_string(), "SQL parse error: unexpected end of input");
+
+        let err =
+            ReplError::parse_error("Expected identifier at Line: 3, Column 7".to_string(), None);
+        assert_eq!(
+            err.to_string(),
+            "SQL parse error: Expected identifier (line 3, column 7)"
+        );
+    }
+}
@@ -1,33 +1,239 @@
 mod backend;
+mod batch;
 mod cli;
-use std::{ops::Deref, process, thread};
+mod config;
+mod error;
+mod pager;
 
-use backend::DataFusionBackend;
-use cli::{connect, describe, head, list, schema, sql, ConnectOpts};
-use cli::{DescribeOpts, HeadOpts, ListOpts, SchemaOpts, SqlOpts};
+use std::{
+    fmt,
+    ops::Deref,
+    process, thread,
+    time::{Duration, Instant},
+};
+
+use arrow::array::RecordBatch;
+use backend::{BackendRegistry, BackendType, DataFusionBackend, Histogram, PolarsBackend};
+use clap::ValueEnum;
+use cli::{
+    catalog, connect, describe, disconnect, engine, format, head, hist, list, schema, set, show,
+    sql,
+};
+use cli::{
+    CatalogOpts, ConnectOpts, DescribeOpts, DisconnectOpts, EngineOpts, FormatOpts, HeadOpts,
+    HistOpts, ListOpts, OutputFile, SchemaOpts, SetOpts, ShowOpts, SqlOpts,
+};
 use crossbeam_channel as mpsc;
 use enum_dispatch::enum_dispatch;
 use reedline_repl_rs::CallBackMap;
+use serde::{Deserialize, Serialize};
 
+pub use backend::Catalog;
+pub use batch::run_script;
 pub use cli::ReplCommand;
-use tokio::runtime::Runtime;
+pub use config::Config;
+pub use error::ReplError;
+use tokio::{runtime::Runtime, signal};
 
 #[enum_dispatch]
 trait CmdExector {
-    async fn execute<T: Backend>(self, backend: &mut T) -> anyhow::Result<String>;
+    async fn execute(self, backend: &mut BackendRegistry) -> anyhow::Result<ReplOutput>;
 }
 
+#[enum_dispatch]
 trait Backend {
     async fn connect(&mut self, opts: &ConnectOpts) -> anyhow::Result<()>;
-    async fn list(&self) -> anyhow::Result<impl ReplDisplay>;
-    async fn schema(&self, name: &str) -> anyhow::Result<impl ReplDisplay>;
-    async fn describe(&self, name: &str) -> anyhow::Result<impl ReplDisplay>;
-    async fn head(&self, name: &str, n: usize) -> anyhow::Result<impl ReplDisplay>;
-    async fn sql(&self, query: &str) -> anyhow::Result<impl ReplDisplay>;
+    async fn disconnect(&mut self, name: &str) -> anyhow::Result<()>;
+    async fn list(&self) -> anyhow::Result<ReplResult>;
+    async fn schema(&self, name: &str) -> anyhow::Result<ReplResult>;
+    async fn describe(&self, opts: &DescribeOpts) -> anyhow::Result<ReplResult>;
+    async fn head(&self, name: &str, n: usize) -> anyhow::Result<ReplResult>;
+    async fn hist(&self, opts: &HistOpts) -> anyhow::Result<Histogram>;
+    async fn sql(&self, query: &str) -> anyhow::Result<ReplResult>;
 }
 
+#[enum_dispatch]
 trait ReplDisplay {
-    async fn display(self) -> anyhow::Result<String>;
+    async fn display(self, opts: &DisplayOpts) -> anyhow::Result<ReplOutput>;
+}
+
+#[enum_dispatch]
+trait ReplExport {
+    async fn export(self, output: &OutputFile) -> anyhow::Result<String>;
+}
+
+/// The result of a backend command, whichever engine produced it.
+#[enum_dispatch(ReplDisplay, ReplExport)]
+pub enum ReplResult {
+    DataFusion(datafusion::dataframe::DataFrame),
+    Polars(polars::frame::DataFrame),
+    PolarsLazy(polars::lazy::frame::LazyFrame),
+    RecordBatch(RecordBatch),
+}
+
+impl ReplResult {
+    /// Writes the result to `output` if given, otherwise formats it for the
+    /// terminal.
+    async fn output(
+        self,
+        output: Option<&OutputFile>,
+        opts: &DisplayOpts,
+    ) -> anyhow::Result<ReplOutput> {
+        match output {
+            Some(output) => Ok(self.export(output).await?.into()),
+            None => self.display(opts).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum Engine {
+    #[default]
+    #[value(name = "datafusion")]
+    DataFusion,
+    #[value(name = "polars")]
+    Polars,
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Engine::DataFusion => write!(f, "datafusion"),
+            Engine::Polars => write!(f, "polars"),
+        }
+    }
+}
+
+/// How results are shown in the REPL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayFormat {
+    #[default]
+    #[value(name = "table")]
+    Table,
+    #[value(name = "csv")]
+    Csv,
+    #[value(name = "tsv")]
+    Tsv,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "ndjson")]
+    NdJson,
+    #[value(name = "markdown")]
+    Markdown,
+    /// One field per line, like `\x` in psql
+    #[value(name = "vertical", alias = "expanded")]
+    #[serde(alias = "expanded")]
+    Vertical,
+}
+
+impl fmt::Display for DisplayFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayFormat::Table => write!(f, "table"),
+            DisplayFormat::Csv => write!(f, "csv"),
+            DisplayFormat::Tsv => write!(f, "tsv"),
+            DisplayFormat::Json => write!(f, "json"),
+            DisplayFormat::NdJson => write!(f, "ndjson"),
+            DisplayFormat::Markdown => write!(f, "markdown"),
+            DisplayFormat::Vertical => write!(f, "vertical"),
+        }
+    }
+}
+
+impl DisplayFormat {
+    /// Text formats are split into pages, the machine readable ones are
+    /// printed whole so they can be copied or piped.
+    pub fn paged(&self) -> bool {
+        matches!(
+            self,
+            DisplayFormat::Table | DisplayFormat::Markdown | DisplayFormat::Vertical
+        )
+    }
+}
+
+/// How the results of the session are shown.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayOpts {
+    pub format: DisplayFormat,
+    /// Rows kept from a result for display, 0 to keep them all
+    pub max_rows: usize,
+    /// Rows per page of the pager, 0 to print results whole
+    pub page_size: usize,
+}
+
+impl Default for DisplayOpts {
+    fn default() -> Self {
+        Self {
+            format: DisplayFormat::default(),
+            max_rows: 1000,
+            page_size: 50,
+        }
+    }
+}
+
+impl DisplayOpts {
+    pub fn row_cap(&self) -> usize {
+        match self.max_rows {
+            0 => usize::MAX,
+            n => n,
+        }
+    }
+
+    /// Offset and length of every page of `rows` rows.
+    pub fn pages(&self, rows: usize) -> Vec<(usize, usize)> {
+        if !self.format.paged() || self.page_size == 0 || rows == 0 {
+            return vec![(0, rows)];
+        }
+        (0..rows)
+            .step_by(self.page_size)
+            .map(|offset| (offset, self.page_size.min(rows - offset)))
+            .collect()
+    }
+}
+
+/// What a command prints: one or more pages, shown through the pager when
+/// there are several, and a footer for query results.
+pub struct ReplOutput {
+    pages: Vec<String>,
+    /// Rows in the result and how many of them are shown.
+    rows: Option<(usize, usize)>,
+    elapsed: Option<Duration>,
+}
+
+impl ReplOutput {
+    pub fn paged(pages: Vec<String>, total: usize, shown: usize) -> Self {
+        Self {
+            pages,
+            rows: Some((total, shown)),
+            elapsed: None,
+        }
+    }
+
+    fn footer(&self) -> Option<String> {
+        let (total, shown) = self.rows?;
+        let elapsed = self.elapsed.unwrap_or_default().as_secs_f64();
+        let plural = if total == 1 { "" } else { "s" };
+        if shown < total {
+            Some(format!(
+                "{} row{} ({} shown) in {:.3}s",
+                total, plural, shown, elapsed
+            ))
+        } else {
+            Some(format!("{} row{} in {:.3}s", total, plural, elapsed))
+        }
+    }
+}
+
+impl From<String> for ReplOutput {
+    fn from(s: String) -> Self {
+        Self {
+            pages: vec![s],
+            rows: None,
+            elapsed: None,
+        }
+    }
 }
 
 pub struct ReplContext {
@@ -36,7 +242,7 @@ pub struct ReplContext {
 
 pub struct ReplMsg {
     cmd: ReplCommand,
-    tx: oneshot::Sender<String>,
+    tx: oneshot::Sender<Result<ReplOutput, ReplError>>,
 }
 
 impl Deref for ReplContext {
@@ -53,55 +259,103 @@ pub fn get_callbacks() -> ReplCallbBacks {
     let mut callbacks = CallBackMap::new();
 
     callbacks.insert("connect".to_string(), connect);
+    callbacks.insert("disconnect".to_string(), disconnect);
+    callbacks.insert("catalog".to_string(), catalog);
     callbacks.insert("list".to_string(), list);
     callbacks.insert("schema".to_string(), schema);
     callbacks.insert("describe".to_string(), describe);
     callbacks.insert("head".to_string(), head);
+    callbacks.insert("hist".to_string(), hist);
     callbacks.insert("sql".to_string(), sql);
+    callbacks.insert("engine".to_string(), engine);
+    callbacks.insert("format".to_string(), format);
+    callbacks.insert("set".to_string(), set);
+    callbacks.insert("show".to_string(), show);
 
     callbacks
 }
 
 impl Default for ReplContext {
     fn default() -> Self {
-        Self::new()
+        Self::new(Engine::default(), Config::default(), Catalog::default())
     }
 }
 
 impl ReplContext {
-    pub fn new() -> Self {
+    pub fn new(engine: Engine, config: Config, catalog: Catalog) -> Self {
         let (tx, rx) = mpsc::unbounded::<ReplMsg>();
 
         let rt = Runtime::new().expect("Failed to create Tokio runtime");
-        let mut backend = DataFusionBackend::new();
         thread::Builder::new()
             .name("ReplBackend".to_string())
             .spawn(move || {
-                while let Ok(msg) = rx.recv() {
-                    if let Err(e) = rt.block_on(async {
-                        let ret = msg.cmd.execute(&mut backend).await?;
-                        msg.tx.send(ret)?;
-                        Ok::<_, anyhow::Error>(())
-                    }) {
-                        eprintln!("Failed to process command: {}", e);
+                let mut backend = BackendRegistry::new(engine, config, catalog);
+                match rt.block_on(backend.restore()) {
+                    Ok(failed) => {
+                        for (name, e) in failed {
+                            eprintln!("Dataset {} is not available: {}", name, e);
+                        }
                     }
+                    Err(e) => eprintln!("Failed to load the catalog: {}", e),
+                }
+                while let Ok(msg) = rx.recv() {
+                    let query = msg.cmd.query().map(str::to_string);
+                    let ret = rt.block_on(async {
+                        let start = Instant::now();
+                        // dropping the command future on Ctrl-C drops the
+                        // streams it polls, which aborts their tasks
+                        let mut ret: ReplOutput = tokio::select! {
+                            ret = msg.cmd.execute(&mut backend) => ret?,
+                            Ok(()) = signal::ctrl_c() => return Err(ReplError::Cancelled.into()),
+                        };
+                        ret.elapsed = Some(start.elapsed());
+                        Ok::<_, anyhow::Error>(ret)
+                    });
+                    let ret = ret.map_err(|e| ReplError::new(e, query.as_deref()));
+                    // the receiver is only gone if the REPL stopped waiting
+                    let _ = msg.tx.send(ret);
                 }
             })
             .unwrap();
         Self { tx }
     }
 
-    pub fn send(&self, msg: ReplMsg, rx: oneshot::Receiver<String>) -> Option<String> {
+    /// Sends a command to the backend and waits for its output, paging
+    /// through it when it is long, or for the error it failed with.
+    pub fn send(
+        &self,
+        msg: ReplMsg,
+        rx: oneshot::Receiver<Result<ReplOutput, ReplError>>,
+    ) -> Option<String> {
+        if let Err(e) = self.tx.send(msg) {
+            eprintln!("Repl Send Error: {}", e);
+            process::exit(1);
+        }
+        match rx.recv() {
+            Ok(Ok(output)) => Some(pager::show(output)),
+            Ok(Err(e)) => Some(format!("Error: {}", e)),
+            Err(_) => None,
+        }
+    }
+
+    /// Runs a command to completion, returning its whole output.
+    pub fn run(&self, cmd: ReplCommand) -> Result<String, ReplError> {
+        let (msg, rx) = ReplMsg::new(cmd);
         if let Err(e) = self.tx.send(msg) {
             eprintln!("Repl Send Error: {}", e);
             process::exit(1);
         }
-        rx.recv().ok()
+        match rx.recv() {
+            Ok(ret) => ret.map(pager::render),
+            Err(e) => Err(ReplError::Other(e.into())),
+        }
     }
 }
 
 impl ReplMsg {
-    pub fn new(cmd: impl Into<ReplCommand>) -> (Self, oneshot::Receiver<String>) {
+    pub fn new(
+        cmd: impl Into<ReplCommand>,
+    ) -> (Self, oneshot::Receiver<Result<ReplOutput, ReplError>>) {
         let (tx, rx) = oneshot::channel();
         (
             Self {
@@ -112,3 +366,34 @@ impl ReplMsg {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_should_split_paged_formats() {
+        let mut opts = DisplayOpts {
+            format: DisplayFormat::Table,
+            max_rows: 0,
+            page_size: 2,
+        };
+        assert_eq!(opts.pages(5), [(0, 2), (2, 2), (4, 1)]);
+        assert_eq!(opts.pages(4), [(0, 2), (2, 2)]);
+        assert_eq!(opts.pages(0), [(0, 0)]);
+
+        opts.format = DisplayFormat::Csv;
+        assert_eq!(opts.pages(5), [(0, 5)]);
+        opts.format = DisplayFormat::Vertical;
+        opts.page_size = 0;
+        assert_eq!(opts.pages(5), [(0, 5)]);
+    }
+
+    #[test]
+    fn row_cap_should_treat_zero_as_unlimited() {
+        let mut opts = DisplayOpts::default();
+        assert_eq!(opts.row_cap(), 1000);
+        opts.max_rows = 0;
+        assert_eq!(opts.row_cap(), usize::MAX);
+    }
+}
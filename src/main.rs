@@ -1,10 +1,67 @@
+use std::{fs, path::PathBuf, process};
+
+use clap::Parser;
 use reedline_repl_rs::Repl;
-use taotie::{get_callbacks, ReplCommand, ReplContext};
+use taotie::{get_callbacks, run_script, Catalog, Config, Engine, ReplCommand, ReplContext};
+
+#[derive(Debug, Parser)]
+#[command(name = "taotie", version, about = "Taotie, your data analysis tool")]
+struct Args {
+    #[arg(short, long, value_enum, default_value_t = Engine::default(), help = "Query engine to use")]
+    engine: Engine,
+
+    #[arg(
+        long,
+        help = "Rows of a result to display, 0 for all; defaults to the config value"
+    )]
+    max_rows: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Rows per page, 0 to turn the pager off; defaults to the config value"
+    )]
+    page_size: Option<usize>,
+
+    #[arg(
+        short,
+        long,
+        help = "Run the commands of a script file instead of the REPL"
+    )]
+    file: Option<PathBuf>,
 
-const HISTORY_SIZE: usize = 1024;
+    #[arg(
+        short,
+        long,
+        conflicts_with = "file",
+        help = "Run commands separated by ';' instead of the REPL"
+    )]
+    command: Option<String>,
+
+    #[arg(long, help = "Keep running a script after a command fails")]
+    continue_on_error: bool,
+}
 
 fn main() -> anyhow::Result<()> {
-    let ctx = ReplContext::new();
+    let args = Args::parse();
+    let mut config = Config::load()?;
+    if let Some(max_rows) = args.max_rows {
+        config.display.max_rows = max_rows;
+    }
+    if let Some(page_size) = args.page_size {
+        config.display.page_size = page_size;
+    }
+    let (history_size, banner) = (config.history_size, config.banner.clone());
+    let ctx = ReplContext::new(args.engine, config, Catalog::default());
+
+    let script = match args.file {
+        Some(file) => Some(fs::read_to_string(file)?),
+        None => args.command,
+    };
+    if let Some(script) = script {
+        let failed = run_script(&ctx, &script, args.continue_on_error);
+        process::exit(if failed > 0 { 1 } else { 0 });
+    }
+
     let callbacks = get_callbacks();
 
     let history_file = dirs::home_dir()
@@ -12,8 +69,8 @@ fn main() -> anyhow::Result<()> {
         .join(".taotie_history");
 
     let mut repl = Repl::new(ctx)
-        .with_history(history_file, HISTORY_SIZE)
-        .with_banner("Welcome to Taotie, your data analysis tool")
+        .with_history(history_file, history_size)
+        .with_banner(&banner)
         .with_derived::<ReplCommand>(callbacks);
 
     repl.run()?;
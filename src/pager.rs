@@ -0,0 +1,49 @@
+//! A minimal pager for results longer than a page.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::ReplOutput;
+
+/// Pages through `output` when it has several pages and the REPL runs in a
+/// terminal, returning what is left to print.
+pub fn show(output: ReplOutput) -> String {
+    let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+    if output.pages.len() <= 1 || !interactive {
+        return render(output);
+    }
+    let footer = output.footer();
+    let pages = output.pages;
+
+    let mut i = 0;
+    loop {
+        println!("{}", pages[i]);
+        print!(
+            "-- page {}/{} -- [n]ext, [p]rev, [q]uit: ",
+            i + 1,
+            pages.len()
+        );
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        match line.trim() {
+            "" | "n" | "next" if i + 1 < pages.len() => i += 1,
+            "" | "n" | "next" | "q" | "quit" => break,
+            "p" | "prev" => i = i.saturating_sub(1),
+            _ => {}
+        }
+    }
+    footer.unwrap_or_default()
+}
+
+/// All the pages of `output` followed by its footer.
+pub fn render(output: ReplOutput) -> String {
+    let footer = output.footer();
+    let mut ret = output.pages.join("\n");
+    if let Some(footer) = footer {
+        ret.push('\n');
+        ret.push_str(&footer);
+    }
+    ret
+}